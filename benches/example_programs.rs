@@ -38,7 +38,7 @@ fn bench_hello(c: &mut Criterion) {
         run(code)
     }
 
-    c.bench_function("hello", |b| b.iter(|| hello()));
+    c.bench_function("hello", |b| b.iter(hello));
 }
 
 fn bench_quine(c: &mut Criterion) {
@@ -48,7 +48,7 @@ fn bench_quine(c: &mut Criterion) {
         run(code)
     }
 
-    c.bench_function("quine", |b| b.iter(|| quine()));
+    c.bench_function("quine", |b| b.iter(quine));
 }
 
 fn bench_fibo(c: &mut Criterion) {
@@ -58,7 +58,7 @@ fn bench_fibo(c: &mut Criterion) {
         run(code)
     }
 
-    c.bench_function("fibo", |b| b.iter(|| fibo()));
+    c.bench_function("fibo", |b| b.iter(fibo));
 }
 
 criterion_group!(benches, bench_hello, bench_quine, bench_fibo);
@@ -17,15 +17,23 @@
 
 //! Helper types for storing program configuration.
 
+use std::collections::HashMap;
 use std::env;
-use std::fs::File;
+use std::fs::{self, File, OpenOptions};
 use std::io;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
 use crate::data::stack::StackStack;
 use crate::data::Point;
 use crate::data::Value;
+use crate::data::CellSize;
+use crate::data::Overflow;
+use crate::data::cell::{Cell, Operation};
+use crate::program::Fingerprint;
 
 /// Specifies how to react when the program tries to access a file.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -33,7 +41,21 @@ pub enum FileView {
     /// Gives complete access to the real filesystem.
     Real,
     /// Denies any file access. The `i` and `o` instructions will fail and the
-    /// interpreter will report that they are unsupported.
+    /// interpreter will report that they are unsupported. The instructions of
+    /// the `FILE` and `DIRF` fingerprints will fail as well.
+    Deny,
+}
+
+/// Specifies how to react when the program tries to access the network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkView {
+    /// Gives complete access to the network.
+    Real,
+    /// Only allows binding and connecting to loopback addresses, so that the
+    /// program can only communicate with peers on the same host.
+    Loopback,
+    /// Denies any network access. The instructions of the `SOCK` and `SCKE`
+    /// fingerprints will fail.
     Deny,
 }
 
@@ -59,8 +81,18 @@ pub struct Config<'env> {
     input: Box<dyn BufRead + 'env>,
     input_buffer: String,
     output: Box<dyn Write + 'env>,
+    drawing_output: Option<Box<dyn Write + 'env>>,
     file_view: FileView,
+    working_dir: PathBuf,
+    root_dir: Option<PathBuf>,
+    network_view: NetworkView,
     exec_action: ExecAction,
+    cell_size: CellSize,
+    overflow: Overflow,
+    env: Option<Vec<(String, String)>>,
+    files: Vec<Option<OpenFile>>,
+    sockets: Vec<Option<Socket>>,
+    fingerprints: HashMap<Value, Box<dyn Fingerprint + 'env>>,
 }
 
 impl<'env> Config<'env> {
@@ -74,8 +106,18 @@ impl<'env> Config<'env> {
             input: Box::new(BufReader::new(io::stdin())),
             input_buffer: String::new(),
             output: Box::new(io::stdout()),
+            drawing_output: None,
             file_view: FileView::Real,
+            working_dir: PathBuf::new(),
+            root_dir: None,
+            network_view: NetworkView::Real,
             exec_action: ExecAction::Real,
+            cell_size: CellSize::Bits32,
+            overflow: Overflow::Wrap,
+            env: None,
+            files: Vec::new(),
+            sockets: Vec::new(),
+            fingerprints: HashMap::new(),
         }
     }
 
@@ -111,6 +153,17 @@ impl<'env> Config<'env> {
         }
     }
 
+    /// Sets the stream to which drawings of the `TURT` fingerprint are written
+    /// as SVG.
+    ///
+    /// By default, there is no such stream and printing a drawing will fail.
+    pub fn drawing_output(self, output: impl Write + 'env) -> Self {
+        Self {
+            drawing_output: Some(Box::new(output)),
+            ..self
+        }
+    }
+
     /// Sets the [`FileView`] of the `Config`.
     ///
     /// [`FileView`]: enum.FileView.html
@@ -121,6 +174,46 @@ impl<'env> Config<'env> {
         }
     }
 
+    /// Sets the directory relative to which the program accesses files.
+    ///
+    /// By default, this is the working directory of the interpreter. The
+    /// program can change it with the `DIRF` fingerprint. Commands executed
+    /// with `=` run in this directory as well.
+    pub fn working_dir(self, working_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            working_dir: working_dir.into(),
+            ..self
+        }
+    }
+
+    /// Confines the program's file access to the given directory.
+    ///
+    /// Paths that lead outside of it, whether they are absolute, contain `..`
+    /// or pass through symbolic links, are treated as if file access was
+    /// denied. This includes changing the working directory with the `DIRF`
+    /// fingerprint. By default, the program can access any file.
+    ///
+    /// Commands executed with `=` are not confined. Use [`ExecAction::Deny`]
+    /// to prevent them.
+    ///
+    /// [`ExecAction::Deny`]: enum.ExecAction.html#variant.Deny
+    pub fn root_dir(self, root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: Some(root_dir.into()),
+            ..self
+        }
+    }
+
+    /// Sets the [`NetworkView`] of the `Config`.
+    ///
+    /// [`NetworkView`]: enum.NetworkView.html
+    pub fn network_view(self, network_view: NetworkView) -> Self {
+        Self {
+            network_view,
+            ..self
+        }
+    }
+
     /// Sets the [`ExecAction`] of the `Config`.
     ///
     /// [`ExecAction`]: enum.ExecAction.html
@@ -131,6 +224,72 @@ impl<'env> Config<'env> {
         }
     }
 
+    /// Sets the [`CellSize`] of the program.
+    ///
+    /// By default, cells are 32 bits wide.
+    ///
+    /// [`CellSize`]: enum.CellSize.html
+    pub fn cell_size(self, cell_size: CellSize) -> Self {
+        Self {
+            cell_size,
+            ..self
+        }
+    }
+
+    /// Sets how arithmetic [`Overflow`] is handled.
+    ///
+    /// By default, results wrap around.
+    ///
+    /// [`Overflow`]: enum.Overflow.html
+    pub fn overflow(self, overflow: Overflow) -> Self {
+        Self {
+            overflow,
+            ..self
+        }
+    }
+
+    /// Sets the environment variables visible to the program.
+    ///
+    /// By default, these are the environment variables of the interpreter.
+    /// Changes made by the program through the `EVAR` fingerprint only affect
+    /// the `Config`, not the environment of the interpreter.
+    pub fn env<K, V>(self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            env: Some(vars.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+            ..self
+        }
+    }
+
+    /// Registers a [`Fingerprint`] with the `Config` under the given ID.
+    ///
+    /// The ID is the one computed by the `(` instruction from the fingerprint's
+    /// name, e.g. `0x4e554c4c` for `NULL`. A fingerprint registered this way
+    /// takes precedence over a built-in fingerprint with the same ID.
+    ///
+    /// [`Fingerprint`]: trait.Fingerprint.html
+    pub fn fingerprint(mut self, id: Value, fingerprint: impl Fingerprint + 'env) -> Self {
+        self.fingerprints.insert(id, Box::new(fingerprint));
+        self
+    }
+
+    /// Returns the [`CellSize`] of the program.
+    ///
+    /// [`CellSize`]: enum.CellSize.html
+    pub(crate) fn get_cell_size(&self) -> CellSize {
+        self.cell_size
+    }
+
+    /// Returns how arithmetic [`Overflow`] is handled.
+    ///
+    /// [`Overflow`]: enum.Overflow.html
+    pub(crate) fn get_overflow(&self) -> Overflow {
+        self.overflow
+    }
+
     /// Prints the current state of one IP to stderr.
     pub(crate) fn do_trace(&mut self, trace: Trace) {
         if self.trace {
@@ -141,10 +300,19 @@ impl<'env> Config<'env> {
     /// Tries to write a number to the `Config`'s output stream.
     ///
     /// Returns `true` if it succeeded, `false` otherwise.
-    pub(crate) fn write_decimal(&mut self, n: i32) -> bool {
+    pub(crate) fn write_decimal(&mut self, n: &Cell) -> bool {
         write!(self.output, "{} ", n).is_ok()
     }
 
+    /// Tries to write a floating point number to the `Config`'s output stream.
+    ///
+    /// The number is written with six decimal places.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn write_float(&mut self, x: f64) -> bool {
+        write!(self.output, "{:.6} ", x).is_ok()
+    }
+
     /// Tries to write a `char` to the `Config`'s output stream.
     ///
     /// Returns `true` if it succeeded, `false` otherwise.
@@ -152,10 +320,35 @@ impl<'env> Config<'env> {
         write!(self.output, "{}", c).is_ok()
     }
 
+    /// Tries to write a string to the `Config`'s output stream.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn write_string(&mut self, s: &str) -> bool {
+        write!(self.output, "{}", s).is_ok()
+    }
+
+    /// Tries to flush the `Config`'s output stream.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn flush_output(&mut self) -> bool {
+        self.output.flush().is_ok()
+    }
+
+    /// Tries to write a drawing to the `Config`'s drawing output.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn write_drawing(&mut self, svg: &str) -> bool {
+        match &mut self.drawing_output {
+            Some(output) => output.write_all(svg.as_bytes()).and_then(|_| output.flush()).is_ok(),
+            None         => false,
+        }
+    }
+
     /// Tries to read a number from the `Config`'s input stream.
     ///
-    /// Returns `Some` read number if it succeeded, `None` otherwise.
-    pub(crate) fn read_decimal(&mut self) -> Option<i32> {
+    /// Returns `Some` read number if it succeeded, `None` otherwise. The number
+    /// is consumed even if it overflows a cell and overflow is trapped.
+    pub(crate) fn read_decimal(&mut self) -> Option<Cell> {
         if self.output.flush().is_err() {
             return None;
         }
@@ -165,13 +358,17 @@ impl<'env> Config<'env> {
         }
 
         let mut found = false;
-        let mut ret = 0;
+        let mut ret = Some(Cell::default());
         let mut stop = 0;
         for (i, b) in self.input_buffer.bytes().enumerate() {
-            if (b as char).is_digit(10) {
+            if b.is_ascii_digit() {
                 found = true;
-                ret *= 10;
-                ret += i32::from(b - b'0');
+                let (size, overflow) = (self.cell_size, self.overflow);
+                let digit = Cell::from(Value::from(b - b'0'));
+
+                ret = ret
+                    .and_then(|v| Operation::Mul.apply(&v, &Cell::from(10), size, overflow))
+                    .and_then(|v| Operation::Add.apply(&v, &digit, size, overflow));
             } else if found {
                 if b == b'\n' {
                     stop = i + 1;
@@ -185,7 +382,7 @@ impl<'env> Config<'env> {
 
         // TODO Should this return 0 if no digits were encountered? That seems to be what it's
         // doing right now. Consult the specification about this.
-        Some(ret)
+        ret
     }
 
     /// Tries to read a `char` from the `Config`'s input stream.
@@ -212,14 +409,43 @@ impl<'env> Config<'env> {
         Some(c)
     }
 
+    /// Tries to read a line from the `Config`'s input stream.
+    ///
+    /// Returns `Some` read line without its line terminator if it succeeded,
+    /// `None` otherwise.
+    pub(crate) fn read_line(&mut self) -> Option<String> {
+        if self.output.flush().is_err() {
+            return None;
+        }
+
+        if self.input_buffer.is_empty() {
+            match self.input.read_line(&mut self.input_buffer) {
+                Ok(0) | Err(_) => return None,
+                Ok(_)          => (),
+            }
+        }
+
+        let mut line: String = self.input_buffer.drain(..).collect();
+
+        if line.ends_with('\n') {
+            line.pop();
+
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+
+        Some(line)
+    }
+
     /// Tries to write the given string to a file.
     ///
     /// Returns `true` if it succeeded, `false` otherwise.
     pub(crate) fn write_file(&self, path: &str, data: &str) -> bool {
-        match self.file_view {
-            FileView::Real => (),
-            FileView::Deny  => return false,
-        }
+        let path = match self.resolve_path(path) {
+            Some(path) => path,
+            None       => return false,
+        };
 
         let mut f = match File::create(path) {
             Ok(f)  => f,
@@ -233,10 +459,7 @@ impl<'env> Config<'env> {
     ///
     /// Returns `Some` read string, or `None` if it failed.
     pub(crate) fn read_file(&self, path: &str) -> Option<String> {
-        match self.file_view {
-            FileView::Real => (),
-            FileView::Deny  => return None,
-        }
+        let path = self.resolve_path(path)?;
 
         let mut f = match File::open(path) {
             Ok(f)  => f,
@@ -252,8 +475,417 @@ impl<'env> Config<'env> {
         }
     }
 
+    /// Tries to open a file for use with the `FILE` fingerprint.
+    ///
+    /// The mode is given as by the `O` instruction of the fingerprint: `0` for
+    /// reading, `1` for writing, `2` for appending, and `3` to `5` for the same
+    /// modes with both reading and writing enabled. The [`Point`] is the
+    /// location of the file's I/O buffer in funge space.
+    ///
+    /// Returns `Some` handle to the opened file, or `None` if it failed.
+    ///
+    /// [`Point`]: ../data/struct.Point.html
+    pub(crate) fn open_file(&mut self, path: &str, mode: Value, buffer: Point) -> Option<Value> {
+        let path = self.resolve_path(path)?;
+        let mut options = OpenOptions::new();
+
+        match mode {
+            0 => options.read(true),
+            1 => options.write(true).create(true).truncate(true),
+            2 => options.append(true).create(true),
+            3 => options.read(true).write(true),
+            4 => options.read(true).write(true).create(true).truncate(true),
+            5 => options.read(true).append(true).create(true),
+            _ => return None,
+        };
+
+        let file = options.open(path).ok()?;
+
+        Some(insert_handle(&mut self.files, OpenFile { file, buffer }))
+    }
+
+    /// Closes the file with the given handle.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn close_file(&mut self, handle: Value) -> bool {
+        match self.files.get_mut(handle as usize) {
+            Some(open) if handle >= 0 => open.take().is_some(),
+            _                         => false,
+        }
+    }
+
+    /// Returns the location of the I/O buffer of the file with the given
+    /// handle.
+    pub(crate) fn file_buffer(&self, handle: Value) -> Option<Point> {
+        self.open_file_ref(handle).map(|open| open.buffer)
+    }
+
+    /// Tries to read up to `n` bytes from the file with the given handle.
+    ///
+    /// Returns `Some` read bytes, or `None` if it failed. Fewer than `n` bytes
+    /// are returned if the end of the file was reached.
+    pub(crate) fn read_from_file(&mut self, handle: Value, n: usize) -> Option<Vec<u8>> {
+        let open = self.open_file_mut(handle)?;
+        let mut data = Vec::new();
+
+        (&mut open.file).take(n as u64).read_to_end(&mut data).ok()?;
+
+        Some(data)
+    }
+
+    /// Tries to read a line from the file with the given handle.
+    ///
+    /// Returns `Some` read bytes including the line terminator, or `None` if it
+    /// failed. An empty vector is returned at the end of the file.
+    pub(crate) fn read_line_from_file(&mut self, handle: Value) -> Option<Vec<u8>> {
+        let open = self.open_file_mut(handle)?;
+        let mut data = Vec::new();
+        let mut b = [0];
+
+        loop {
+            match open.file.read(&mut b) {
+                Ok(0)  => break,
+                Ok(_)  => data.push(b[0]),
+                Err(_) => return None,
+            }
+
+            if b[0] == b'\n' {
+                break;
+            }
+        }
+
+        Some(data)
+    }
+
+    /// Tries to write the given bytes to the file with the given handle.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn write_to_file(&mut self, handle: Value, data: &[u8]) -> bool {
+        match self.open_file_mut(handle) {
+            Some(open) => open.file.write_all(data).is_ok(),
+            None       => false,
+        }
+    }
+
+    /// Moves the file pointer of the file with the given handle.
+    ///
+    /// The offset is taken from the start of the file if `mode` is 0, from
+    /// the current position if it is 1 and from the end of the file if it is 2.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn seek_file(&mut self, handle: Value, mode: Value, offset: Value) -> bool {
+        let pos = match mode {
+            0 if offset >= 0 => SeekFrom::Start(offset as u64),
+            1                => SeekFrom::Current(offset),
+            2                => SeekFrom::End(offset),
+            _                => return false,
+        };
+
+        match self.open_file_mut(handle) {
+            Some(open) => open.file.seek(pos).is_ok(),
+            None       => false,
+        }
+    }
+
+    /// Returns the position of the file pointer of the file with the given
+    /// handle.
+    pub(crate) fn file_position(&mut self, handle: Value) -> Option<Value> {
+        let open = self.open_file_mut(handle)?;
+
+        open.file.stream_position().ok().map(|pos| pos as Value)
+    }
+
+    /// Tries to delete a file.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn delete_file(&self, path: &str) -> bool {
+        match self.resolve_path(path) {
+            Some(path) => fs::remove_file(path).is_ok(),
+            None       => false,
+        }
+    }
+
+    /// Tries to change the directory relative to which files are accessed.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn change_dir(&mut self, path: &str) -> bool {
+        match self.resolve_path(path) {
+            Some(path) if path.is_dir() => {
+                self.working_dir = path;
+                true
+            },
+            _                           => false,
+        }
+    }
+
+    /// Tries to create a directory.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn make_dir(&self, path: &str) -> bool {
+        match self.resolve_path(path) {
+            Some(path) => fs::create_dir(path).is_ok(),
+            None       => false,
+        }
+    }
+
+    /// Tries to remove an empty directory.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn remove_dir(&self, path: &str) -> bool {
+        match self.resolve_path(path) {
+            Some(path) => fs::remove_dir(path).is_ok(),
+            None       => false,
+        }
+    }
+
+    /// Returns the path through which the program accesses the given file.
+    ///
+    /// Relative paths are resolved against the `Config`'s working directory.
+    /// Returns `None` if the [`FileView`] denies any file access or if the
+    /// path leads outside of the root directory.
+    ///
+    /// [`FileView`]: enum.FileView.html
+    fn resolve_path(&self, path: &str) -> Option<PathBuf> {
+        if self.file_view == FileView::Deny {
+            return None;
+        }
+
+        let path = self.working_dir.join(path);
+
+        match &self.root_dir {
+            Some(root) => {
+                let path = real_path(&path)?;
+
+                if path.starts_with(real_path(root)?) {
+                    Some(path)
+                } else {
+                    None
+                }
+            },
+            None       => Some(path),
+        }
+    }
+
+    fn open_file_ref(&self, handle: Value) -> Option<&OpenFile> {
+        if handle < 0 {
+            return None;
+        }
+
+        self.files.get(handle as usize)?.as_ref()
+    }
+
+    fn open_file_mut(&mut self, handle: Value) -> Option<&mut OpenFile> {
+        if handle < 0 {
+            return None;
+        }
+
+        self.files.get_mut(handle as usize)?.as_mut()
+    }
+
+    /// Tries to create a TCP socket for use with the `SOCK` fingerprint.
+    ///
+    /// Returns `Some` handle to the socket, or `None` if the [`NetworkView`]
+    /// denies network access.
+    ///
+    /// [`NetworkView`]: enum.NetworkView.html
+    pub(crate) fn create_socket(&mut self) -> Option<Value> {
+        if self.network_view == NetworkView::Deny {
+            return None;
+        }
+
+        Some(insert_handle(&mut self.sockets, Socket::Unbound))
+    }
+
+    /// Closes the socket with the given handle.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn close_socket(&mut self, handle: Value) -> bool {
+        match self.sockets.get_mut(handle as usize) {
+            Some(socket) if handle >= 0 => match socket.take() {
+                Some(Socket::Stream(stream)) => {
+                    let _ = stream.shutdown(Shutdown::Both);
+                    true
+                },
+                Some(_)                      => true,
+                None                         => false,
+            },
+            _                           => false,
+        }
+    }
+
+    /// Tries to bind an unbound socket to the given address.
+    ///
+    /// The operating system already queues incoming connections once the
+    /// socket is bound, but they are only accepted after it starts listening.
+    ///
+    /// Returns `true` if it succeeded, `false` if binding failed or the
+    /// [`NetworkView`] denies access to the address.
+    ///
+    /// [`NetworkView`]: enum.NetworkView.html
+    pub(crate) fn bind_socket(&mut self, handle: Value, addr: SocketAddr) -> bool {
+        if !self.allows(addr) {
+            return false;
+        }
+
+        match self.socket_mut(handle) {
+            Some(socket @ Socket::Unbound) => match TcpListener::bind(addr) {
+                Ok(listener) => {
+                    *socket = Socket::Bound(listener);
+                    true
+                },
+                Err(_)       => false,
+            },
+            _                              => false,
+        }
+    }
+
+    /// Tries to make a bound socket listen for connections.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn listen_socket(&mut self, handle: Value) -> bool {
+        let socket = match self.socket_mut(handle) {
+            Some(socket) => socket,
+            None         => return false,
+        };
+
+        match mem::replace(socket, Socket::Unbound) {
+            Socket::Bound(listener) => {
+                *socket = Socket::Listener(listener);
+                true
+            },
+            other                   => {
+                *socket = other;
+                false
+            },
+        }
+    }
+
+    /// Waits for a connection on a listening socket.
+    ///
+    /// Returns `Some` handle to a new socket for the connection together with
+    /// the address of the peer, or `None` if it failed.
+    pub(crate) fn accept_socket(&mut self, handle: Value) -> Option<(Value, SocketAddr)> {
+        let (stream, addr) = match self.socket_mut(handle)? {
+            Socket::Listener(listener) => listener.accept().ok()?,
+            _                          => return None,
+        };
+
+        Some((insert_handle(&mut self.sockets, Socket::Stream(stream)), addr))
+    }
+
+    /// Tries to connect an unbound socket to the given address.
+    ///
+    /// Returns `true` if it succeeded, `false` otherwise.
+    pub(crate) fn connect_socket(&mut self, handle: Value, addr: SocketAddr) -> bool {
+        if !self.allows(addr) {
+            return false;
+        }
+
+        match self.socket_mut(handle) {
+            Some(socket @ Socket::Unbound) => match TcpStream::connect(addr) {
+                Ok(stream) => {
+                    *socket = Socket::Stream(stream);
+                    true
+                },
+                Err(_)     => false,
+            },
+            _                              => false,
+        }
+    }
+
+    /// Tries to receive up to `n` bytes from a connected socket.
+    ///
+    /// Returns `Some` received bytes, or `None` if it failed. An empty vector
+    /// is returned if the connection has been closed by the peer.
+    pub(crate) fn receive_from_socket(&mut self, handle: Value, n: usize) -> Option<Vec<u8>> {
+        let stream = self.stream_mut(handle)?;
+        let mut data = vec![0; n];
+        let len = stream.read(&mut data).ok()?;
+
+        data.truncate(len);
+        Some(data)
+    }
+
+    /// Tries to send the given bytes over a connected socket.
+    ///
+    /// Returns `Some` number of bytes sent, or `None` if it failed.
+    pub(crate) fn send_to_socket(&mut self, handle: Value, data: &[u8]) -> Option<usize> {
+        self.stream_mut(handle)?.write(data).ok()
+    }
+
+    /// Returns the number of bytes that can be received from a connected
+    /// socket without waiting.
+    pub(crate) fn peek_socket(&mut self, handle: Value) -> Option<usize> {
+        let stream = self.stream_mut(handle)?;
+        let mut data = [0; 1024];
+
+        stream.set_nonblocking(true).ok()?;
+
+        let n = match stream.peek(&mut data) {
+            Ok(n)                                                => Some(n),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Some(0),
+            Err(_)                                               => None,
+        };
+
+        stream.set_nonblocking(false).ok()?;
+        n
+    }
+
+    /// Checks whether a socket with the given handle exists.
+    pub(crate) fn has_socket(&mut self, handle: Value) -> bool {
+        self.socket_mut(handle).is_some()
+    }
+
+    /// Looks up the IPv4 address of a host.
+    ///
+    /// Returns `None` if the lookup failed or if the [`NetworkView`] does not
+    /// allow access to the host.
+    ///
+    /// [`NetworkView`]: enum.NetworkView.html
+    pub(crate) fn lookup_host(&self, host: &str) -> Option<Ipv4Addr> {
+        if self.network_view == NetworkView::Deny {
+            return None;
+        }
+
+        (host, 0).to_socket_addrs()
+            .ok()?
+            .filter(|&addr| self.allows(addr))
+            .find_map(|addr| match addr {
+                SocketAddr::V4(addr) => Some(*addr.ip()),
+                SocketAddr::V6(_)    => None,
+            })
+    }
+
+    /// Checks whether the [`NetworkView`] allows access to an address.
+    ///
+    /// [`NetworkView`]: enum.NetworkView.html
+    fn allows(&self, addr: SocketAddr) -> bool {
+        match self.network_view {
+            NetworkView::Real     => true,
+            NetworkView::Loopback => addr.ip().is_loopback(),
+            NetworkView::Deny     => false,
+        }
+    }
+
+    fn socket_mut(&mut self, handle: Value) -> Option<&mut Socket> {
+        if handle < 0 {
+            return None;
+        }
+
+        self.sockets.get_mut(handle as usize)?.as_mut()
+    }
+
+    fn stream_mut(&mut self, handle: Value) -> Option<&mut TcpStream> {
+        match self.socket_mut(handle)? {
+            Socket::Stream(stream) => Some(stream),
+            _                      => None,
+        }
+    }
+
     /// Takes a string and tries to execute it with `sh`.
     ///
+    /// The command runs in the `Config`'s working directory.
+    ///
     /// Returns `Some` [`Value`] with `sh`'s exit code if it was able to obtain
     /// it, and `None` otherwise.
     ///
@@ -268,8 +900,16 @@ impl<'env> Config<'env> {
     /// [`Value`]: ../../data/type.Value.html
     pub(crate) fn execute(&self, cmd: &str) -> Option<Value> {
         if self.exec_action != ExecAction::Deny {
-            match Command::new("sh").args(&["-c", cmd]).status() {
-                Ok(st) => st.code(),
+            let mut command = Command::new("sh");
+
+            command.args(["-c", cmd]);
+
+            if !self.working_dir.as_os_str().is_empty() {
+                command.current_dir(&self.working_dir);
+            }
+
+            match command.status() {
+                Ok(st) => st.code().map(Value::from),
                 Err(_) => None,
             }
         } else {
@@ -277,6 +917,21 @@ impl<'env> Config<'env> {
         }
     }
 
+    /// Returns the [`Fingerprint`] registered under the given ID, if any.
+    ///
+    /// [`Fingerprint`]: ../program/trait.Fingerprint.html
+    pub(crate) fn find_fingerprint(&self, id: Value) -> Option<&(dyn Fingerprint + 'env)> {
+        self.fingerprints.get(&id).map(|fp| &**fp)
+    }
+
+    /// Returns the [`Fingerprint`] registered under the given ID mutably, if
+    /// any.
+    ///
+    /// [`Fingerprint`]: ../program/trait.Fingerprint.html
+    pub(crate) fn find_fingerprint_mut(&mut self, id: Value) -> Option<&mut (dyn Fingerprint + 'env)> {
+        self.fingerprints.get_mut(&id).map(|fp| &mut **fp)
+    }
+
     /// Returns flags containing information about functionality available to
     /// the program.
     ///
@@ -314,10 +969,107 @@ impl<'env> Config<'env> {
         env::args().rev()
     }
 
+    /// Returns the environment variables visible to the program.
+    ///
+    /// Unless they have been set with [`env`], they are copied from the
+    /// environment of the interpreter on first use. Names and values that are
+    /// not valid Unicode are converted lossily.
+    ///
+    /// [`env`]: #method.env
+    fn env_table(&mut self) -> &mut Vec<(String, String)> {
+        self.env.get_or_insert_with(|| {
+            env::vars_os()
+                .map(|(k, v)| (k.to_string_lossy().into_owned(), v.to_string_lossy().into_owned()))
+                .collect()
+        })
+    }
+
     /// Returns an iterator over the environment variables.
-    pub(crate) fn env_vars(&self) -> impl Iterator<Item = (String, String)> {
-        env::vars()
+    pub(crate) fn env_vars(&mut self) -> impl Iterator<Item = &(String, String)> {
+        self.env_table().iter()
+    }
+
+    /// Returns the value of an environment variable, if it is set.
+    pub(crate) fn get_env(&mut self, name: &str) -> Option<&str> {
+        self.env_table().iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
     }
+
+    /// Sets an environment variable, replacing its previous value.
+    pub(crate) fn set_env(&mut self, name: &str, value: &str) {
+        let env = self.env_table();
+
+        match env.iter_mut().find(|(k, _)| k == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None         => env.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+impl<'env> Default for Config<'env> {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+/// A file opened by the `FILE` fingerprint.
+struct OpenFile {
+    file: File,
+    buffer: Point,
+}
+
+/// A socket created by the `SOCK` fingerprint.
+enum Socket {
+    /// A socket that has neither been bound nor connected yet.
+    Unbound,
+    /// A socket that has been bound to an address, but is not listening yet.
+    Bound(TcpListener),
+    /// A socket listening for connections.
+    Listener(TcpListener),
+    /// A connected socket.
+    Stream(TcpStream),
+}
+
+/// Stores an item in the first free slot of a handle table.
+///
+/// Returns the handle of the item.
+fn insert_handle<T>(table: &mut Vec<Option<T>>, item: T) -> Value {
+    match table.iter().position(Option::is_none) {
+        Some(i) => {
+            table[i] = Some(item);
+            i as Value
+        },
+        None    => {
+            table.push(Some(item));
+            table.len() as Value - 1
+        },
+    }
+}
+
+/// Returns the absolute path that `path` leads to.
+///
+/// Symbolic links are resolved as far as the path exists, and `.` and `..`
+/// components are removed.
+fn real_path(path: &Path) -> Option<PathBuf> {
+    let mut real = PathBuf::new();
+
+    for component in env::current_dir().ok()?.join(path).components() {
+        match component {
+            Component::CurDir       => (),
+            Component::ParentDir    => {
+                real.pop();
+            },
+            Component::Normal(name) => {
+                real.push(name);
+
+                if let Ok(path) = real.canonicalize() {
+                    real = path;
+                }
+            },
+            _                       => real.push(component),
+        }
+    }
+
+    Some(real)
 }
 
 /// Values available to trace output.
@@ -329,7 +1081,7 @@ pub struct Trace<'a> {
 }
 
 impl<'a> Trace<'a> {
-    pub(crate) fn new(id: i32, command: char, position: Point, stacks: &'a StackStack) -> Self {
+    pub(crate) fn new(id: Value, command: char, position: Point, stacks: &'a StackStack) -> Self {
         Self {
             id,
             command,
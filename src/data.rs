@@ -17,39 +17,136 @@
 
 //! Various types and data structures used for representing program state.
 
+pub(crate) mod cell;
 pub(crate) mod space;
 pub(crate) mod stack;
 
+use std::convert::TryFrom;
 use std::fmt;
 use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign};
 
-const SPACE: i32 = ' ' as i32;
+pub use self::cell::{CellSize, Overflow};
+
+pub(crate) const SPACE: Value = ' ' as Value;
 
 /// The universal type of data upon which a Befunge-98 program operates.
-pub(crate) type Value = i32;
+pub(crate) type Value = i64;
+
+/// Returns the character with the code point `v`, if there is one.
+pub(crate) fn to_char(v: Value) -> Option<char> {
+    u32::try_from(v).ok().and_then(std::char::from_u32)
+}
+
+/// Converts a floating point number to a [`Value`], rounding towards zero.
+///
+/// Like any other number that does not fit into a [`Value`], the result wraps
+/// around if it is out of range. NaN and infinities are converted to `0`.
+///
+/// [`Value`]: type.Value.html
+pub(crate) fn from_float(x: f64) -> Value {
+    if x.is_finite() {
+        // The remainder is exact, and truncating an i128 keeps the low bits.
+        (x.trunc() % 18_446_744_073_709_551_616.0) as i128 as Value
+    } else {
+        0
+    }
+}
+
+/// The number of dimensions of the space a program resides in.
+///
+/// This trait is implemented by [`Unefunge`], [`Befunge`] and [`Trefunge`],
+/// which select the dialect of Funge-98 a [`Program`] runs. It cannot be
+/// implemented outside of this crate.
+///
+/// [`Unefunge`]: struct.Unefunge.html
+/// [`Befunge`]: struct.Befunge.html
+/// [`Trefunge`]: struct.Trefunge.html
+/// [`Program`]: struct.Program.html
+pub trait Dimension: Copy + 'static + private::Sealed {
+    /// The number of components of a vector.
+    const COUNT: usize;
+}
+
+/// Unefunge-98, a single line of cells.
+///
+/// Only the first line of the source code can be reached. Vectors consist of a
+/// single component, and the instructions that would leave the line, like `^`,
+/// `v`, `|`, `[`, `]` and `w`, reflect the IP.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Unefunge;
+
+/// Befunge-98, a plane of cells.
+///
+/// Only the first plane of the source code can be reached, and form feeds in it
+/// are ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Befunge;
+
+/// Trefunge-98, a stack of planes.
+///
+/// A form feed in the source code starts the next plane, and the `h`, `l` and
+/// `m` instructions move the IP between planes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Trefunge;
+
+impl Dimension for Unefunge {
+    const COUNT: usize = 1;
+}
+
+impl Dimension for Befunge {
+    const COUNT: usize = 2;
+}
+
+impl Dimension for Trefunge {
+    const COUNT: usize = 3;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::Unefunge {}
+    impl Sealed for super::Befunge {}
+    impl Sealed for super::Trefunge {}
+}
 
 /// A point in funge space.
+///
+/// The y coordinate is always `0` in a Unefunge program, the z coordinate
+/// unless the program is a Trefunge program.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub(crate) struct Point {
+pub struct Point {
     /// The x coordinate of the point.
-    pub(crate) x: i32,
+    pub x: i32,
     /// The y coordinate of the point.
-    pub(crate) y: i32,
+    pub y: i32,
+    /// The z coordinate of the point.
+    pub z: i32,
 }
 
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        if self.z == 0 {
+            write!(f, "({}, {})", self.x, self.y)
+        } else {
+            write!(f, "({}, {}, {})", self.x, self.y, self.z)
+        }
     }
 }
 
 /// An offset vector in funge space.
+///
+/// Like the coordinates of a [`Point`], the components a program's space does
+/// not have are `0`.
+///
+/// [`Point`]: struct.Point.html
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub(crate) struct Delta {
+pub struct Delta {
     /// The x component of the offset vector.
-    pub(crate) dx: i32,
+    pub dx: i32,
     /// The y component of the offset vector.
-    pub(crate) dy: i32,
+    pub dy: i32,
+    /// The z component of the offset vector.
+    pub dz: i32,
 }
 
 impl Delta {
@@ -58,29 +155,38 @@ impl Delta {
         Delta {
             dx: -self.dx,
             dy: -self.dy,
+            dz: -self.dz,
         }
     }
 
-    /// Returns the original `Delta` rotated 90 degrees to the left.
+    /// Returns the original `Delta` rotated 90 degrees to the left about the z
+    /// axis.
     pub(crate) fn rotate_left(self) -> Self {
         Delta {
             dx: self.dy,
             dy: -self.dx,
+            dz: self.dz,
         }
     }
 
-    /// Returns the original `Delta` rotated 90 degrees to the right.
+    /// Returns the original `Delta` rotated 90 degrees to the right about the
+    /// z axis.
     pub(crate) fn rotate_right(self) -> Self {
         Delta {
             dx: -self.dy,
             dy: self.dx,
+            dz: self.dz,
         }
     }
 }
 
 impl fmt::Display for Delta {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({}, {})", self.dx, self.dy)
+        if self.dz == 0 {
+            write!(f, "({}, {})", self.dx, self.dy)
+        } else {
+            write!(f, "({}, {}, {})", self.dx, self.dy, self.dz)
+        }
     }
 }
 
@@ -89,16 +195,18 @@ impl Add<Delta> for Point {
 
     fn add(self, delta: Delta) -> Self {
         Point {
-            x: self.x + delta.dx,
-            y: self.y + delta.dy,
+            x: self.x.wrapping_add(delta.dx),
+            y: self.y.wrapping_add(delta.dy),
+            z: self.z.wrapping_add(delta.dz),
         }
     }
 }
 
 impl AddAssign<Delta> for Point {
     fn add_assign(&mut self, delta: Delta) {
-        self.x += delta.dx;
-        self.y += delta.dy;
+        self.x = self.x.wrapping_add(delta.dx);
+        self.y = self.y.wrapping_add(delta.dy);
+        self.z = self.z.wrapping_add(delta.dz);
     }
 }
 
@@ -107,16 +215,18 @@ impl Sub<Delta> for Point {
 
     fn sub(self, delta: Delta) -> Self {
         Point {
-            x: self.x - delta.dx,
-            y: self.y - delta.dy,
+            x: self.x.wrapping_sub(delta.dx),
+            y: self.y.wrapping_sub(delta.dy),
+            z: self.z.wrapping_sub(delta.dz),
         }
     }
 }
 
 impl SubAssign<Delta> for Point {
     fn sub_assign(&mut self, delta: Delta) {
-        self.x -= delta.dx;
-        self.y -= delta.dy;
+        self.x = self.x.wrapping_sub(delta.dx);
+        self.y = self.y.wrapping_sub(delta.dy);
+        self.z = self.z.wrapping_sub(delta.dz);
     }
 }
 
@@ -125,15 +235,48 @@ impl Mul<i32> for Delta {
 
     fn mul(self, n: i32) -> Self {
         Delta {
-            dx: self.dx * n,
-            dy: self.dy * n,
+            dx: self.dx.wrapping_mul(n),
+            dy: self.dy.wrapping_mul(n),
+            dz: self.dz.wrapping_mul(n),
         }
     }
 }
 
 impl MulAssign<i32> for Delta {
     fn mul_assign(&mut self, n: i32) {
-        self.dx *= n;
-        self.dy *= n;
+        self.dx = self.dx.wrapping_mul(n);
+        self.dy = self.dy.wrapping_mul(n);
+        self.dz = self.dz.wrapping_mul(n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_float_wraps() {
+        assert_eq!(10_000_000_000, from_float(1e10));
+        assert_eq!(-1, from_float(-1.5));
+        assert_eq!(4096, from_float(18_446_744_073_709_555_712.0));
+        assert_eq!(Value::MIN, from_float(9_223_372_036_854_775_808.0));
+        assert_eq!(0, from_float(f64::NAN));
+    }
+
+    #[test]
+    fn point_add_wraps() {
+        let p = Point { x: i32::MAX, y: 0, z: i32::MIN };
+        let d = Delta { dx: 1, dy: 1, dz: -1 };
+
+        assert_eq!(Point { x: i32::MIN, y: 1, z: i32::MAX }, p + d);
+    }
+
+    #[test]
+    fn point_sub_wraps() {
+        let mut p = Point { x: i32::MIN, y: 0, z: i32::MAX };
+
+        p -= Delta { dx: 1, dy: 1, dz: -1 };
+
+        assert_eq!(Point { x: i32::MAX, y: -1, z: i32::MIN }, p);
     }
 }
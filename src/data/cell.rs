@@ -0,0 +1,271 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The contents of cells and the arithmetic performed on them.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+use num_traits::{CheckedRem, PrimInt, SaturatingMul, ToPrimitive};
+use num_traits::{WrappingAdd, WrappingMul, WrappingSub};
+
+use super::Value;
+
+/// The size of the cells a program computes with.
+///
+/// The `y` instruction reports the size in bytes, or `0` for unbounded cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellSize {
+    /// 32-bit cells, as used by most other interpreters. This is the default.
+    Bits32,
+    /// 64-bit cells.
+    Bits64,
+    /// Cells of arbitrary precision, which never overflow.
+    Unbounded,
+}
+
+impl CellSize {
+    /// Wraps a number around to the range of a cell of this size.
+    ///
+    /// Unbounded cells are treated like 64-bit cells.
+    pub(crate) fn wrap(self, v: Value) -> Value {
+        match self {
+            CellSize::Bits32 => Value::from(v as i32),
+            _                => v,
+        }
+    }
+
+    /// Returns the number of bytes per cell, or `0` if cells are unbounded.
+    pub(crate) fn bytes(self) -> Value {
+        match self {
+            CellSize::Bits32    => 4,
+            CellSize::Bits64    => 8,
+            CellSize::Unbounded => 0,
+        }
+    }
+}
+
+/// What happens when an arithmetic instruction overflows a cell.
+///
+/// This applies to `+`, `-`, `*` and `/` as well as numbers read by `&`. Cells
+/// of [`CellSize::Unbounded`] never overflow.
+///
+/// [`CellSize::Unbounded`]: enum.CellSize.html#variant.Unbounded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// The result wraps around, as in most other interpreters. This is the
+    /// default.
+    Wrap,
+    /// The result is clamped to the range of a cell.
+    Saturate,
+    /// The program is stopped with an [`OverflowError`]. `&` reflects instead.
+    ///
+    /// [`OverflowError`]: struct.OverflowError.html
+    Trap,
+}
+
+/// The contents of a single cell.
+///
+/// Numbers are only stored in the `Big` variant if they do not fit into a
+/// [`Value`], so this can only happen with [`CellSize::Unbounded`].
+///
+/// [`Value`]: ../type.Value.html
+/// [`CellSize::Unbounded`]: enum.CellSize.html#variant.Unbounded
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) enum Cell {
+    Small(Value),
+    Big(Rc<BigInt>),
+}
+
+impl Cell {
+    /// Creates a `Cell` holding the given number.
+    pub(crate) fn from_big(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(v) => Cell::Small(v),
+            None    => Cell::Big(Rc::new(n)),
+        }
+    }
+
+    /// Returns the number held by the `Cell`, wrapped around to the range of
+    /// a [`Value`].
+    ///
+    /// [`Value`]: ../type.Value.html
+    pub(crate) fn value(&self) -> Value {
+        match *self {
+            Cell::Small(v)   => v,
+            Cell::Big(ref n) => {
+                let mut bytes = [0; 8];
+
+                bytes.copy_from_slice(&n.to_signed_bytes_le()[..8]);
+
+                Value::from_le_bytes(bytes)
+            },
+        }
+    }
+
+    /// Returns `true` if the `Cell` holds `0`.
+    pub(crate) fn is_zero(&self) -> bool {
+        *self == Cell::Small(0)
+    }
+
+    fn to_big(&self) -> BigInt {
+        match *self {
+            Cell::Small(v)   => BigInt::from(v),
+            Cell::Big(ref n) => BigInt::clone(n),
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell::Small(0)
+    }
+}
+
+impl From<Value> for Cell {
+    fn from(v: Value) -> Self {
+        Cell::Small(v)
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Cell::Small(a), Cell::Small(b)) => a.cmp(b),
+            _                                => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Cell::Small(v)   => write!(f, "{}", v),
+            Cell::Big(ref n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Debug for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// An arithmetic operation on two cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Operation {
+    /// Applies the operation to the cells `a` and `b` of the given size.
+    ///
+    /// Division and remainder by zero result in zero. Returns `None` if the
+    /// result overflows and `overflow` is [`Overflow::Trap`].
+    ///
+    /// [`Overflow::Trap`]: enum.Overflow.html#variant.Trap
+    pub(crate) fn apply(self, a: &Cell, b: &Cell, size: CellSize, overflow: Overflow) -> Option<Cell> {
+        if b.is_zero() && (self == Operation::Div || self == Operation::Rem) {
+            return Some(Cell::default());
+        }
+
+        match size {
+            CellSize::Bits32    => {
+                let v = self.apply_int(a.value() as i32, b.value() as i32, overflow)?;
+
+                Some(Cell::Small(Value::from(v)))
+            },
+            CellSize::Bits64    => self.apply_int(a.value(), b.value(), overflow).map(Cell::Small),
+            CellSize::Unbounded => {
+                if let (&Cell::Small(x), &Cell::Small(y)) = (a, b) {
+                    if let Some(v) = self.checked_int(x, y) {
+                        return Some(Cell::Small(v));
+                    }
+                }
+
+                Some(Cell::from_big(self.apply_big(a.to_big(), b.to_big())))
+            },
+        }
+    }
+
+    /// Applies the operation to two fixed-size integers, where `b` is not zero
+    /// for division and remainder.
+    ///
+    /// The only division that overflows is that of the minimum by `-1`. The
+    /// corresponding remainder is always zero.
+    fn apply_int<T>(self, a: T, b: T, overflow: Overflow) -> Option<T>
+    where
+        T: PrimInt + CheckedRem + WrappingAdd + WrappingSub + WrappingMul + SaturatingMul,
+    {
+        let checked = match self {
+            Operation::Add => a.checked_add(&b),
+            Operation::Sub => a.checked_sub(&b),
+            Operation::Mul => a.checked_mul(&b),
+            Operation::Div => a.checked_div(&b),
+            Operation::Rem => return Some(a.checked_rem(&b).unwrap_or_else(T::zero)),
+        };
+
+        checked.or_else(|| match overflow {
+            Overflow::Wrap     => Some(match self {
+                Operation::Add => a.wrapping_add(&b),
+                Operation::Sub => a.wrapping_sub(&b),
+                Operation::Mul => a.wrapping_mul(&b),
+                _              => T::min_value(),
+            }),
+            Overflow::Saturate => Some(match self {
+                Operation::Add => a.saturating_add(b),
+                Operation::Sub => a.saturating_sub(b),
+                Operation::Mul => a.saturating_mul(&b),
+                _              => T::max_value(),
+            }),
+            Overflow::Trap     => None,
+        })
+    }
+
+    fn checked_int(self, a: Value, b: Value) -> Option<Value> {
+        match self {
+            Operation::Add => a.checked_add(b),
+            Operation::Sub => a.checked_sub(b),
+            Operation::Mul => a.checked_mul(b),
+            Operation::Div => a.checked_div(b),
+            Operation::Rem => a.checked_rem(b),
+        }
+    }
+
+    fn apply_big(self, a: BigInt, b: BigInt) -> BigInt {
+        match self {
+            Operation::Add => a + b,
+            Operation::Sub => a - b,
+            Operation::Mul => a * b,
+            Operation::Div => a / b,
+            Operation::Rem => a % b,
+        }
+    }
+}
@@ -15,66 +15,88 @@
 // You should have received a copy of the GNU General Public License
 // along with mycon.  If not, see <https://www.gnu.org/licenses/>.
 
-//! The two-dimensional space addressable by a Befunge-98 program.
+//! The space addressable by a Funge-98 program.
 
 mod tree;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
 
-use super::{Value, Point, Delta, SPACE};
+use super::{Dimension, Value, Point, Delta, SPACE};
+use super::cell::Cell;
 use self::tree::*;
 
-/// The space in which a Befunge-98 program resides.
+/// The space in which a Funge-98 program resides.
 ///
-/// Internally, the space is represented by a data structure similar to a
-/// quadtree, though each subdivision partitions the region into a 16x16 grid of
-/// subtrees instead of 2x2. The entire theoretically addressable space is
-/// represented by a tree of depth 8.
+/// Internally, each plane of the space is represented by a data structure
+/// similar to a quadtree, though each subdivision partitions the region into a
+/// 16x16 grid of subtrees instead of 2x2. The entire theoretically addressable
+/// plane is represented by a tree of depth 8. Only Trefunge programs use planes
+/// other than the one at z = 0.
 ///
 /// Memory for representing parts of this tree will be allocated when data is
 /// written to a previously empty region. An uninitialized portion of the tree
 /// represents a region containing only empty space (' ' characters), which is
 /// completely transparent from the point of view of the program.
+///
+/// Numbers that do not fit into a [`Value`] are kept separately, and only
+/// [`get_cell`] returns them in full.
+///
+/// The [`Dimension`] `D` decides how source code is read into the `Space`.
+///
+/// [`Value`]: ../type.Value.html
+/// [`get_cell`]: #method.get_cell
+/// [`Dimension`]: ../trait.Dimension.html
 #[derive(Clone)]
-pub(crate) struct Space {
-    tree: FungeTree,
+pub(crate) struct Space<D: Dimension> {
+    tree: PlaneTree,
+    big: HashMap<Point, Cell>,
     bounds: Bounds,
+    dimension: PhantomData<D>,
 }
 
-impl Space {
+impl<D: Dimension> Space<D> {
     /// Creates a new empty `Space`.
     pub(crate) fn new() -> Self {
         Space {
-            tree: FungeTree::default(),
+            tree: PlaneTree::default(),
+            big: HashMap::new(),
             bounds: Bounds::new(),
+            dimension: PhantomData,
         }
     }
 
     /// Creates a new `Space` containing the given source code.
+    ///
+    /// In Trefunge, a form feed moves on to the first line of the next plane.
+    /// Otherwise, form feeds are ignored.
     pub(crate) fn read(code: &str) -> Self {
         let mut space = Space::new();
-        let mut longest = 0;
-        let mut n_lines = 0;
+        let mut y = 0;
+        let mut z = 0;
 
-        for (y, l) in code.lines().enumerate() {
-            let n = space.set_line(y as i32, l);
-            n_lines += 1;
+        for line in code.lines() {
+            let mut x = 0;
 
-            if n > longest {
-                longest = n;
-            }
-        }
+            for c in line.chars() {
+                let v = c as Value;
 
-        for x in 0..longest as i32 {
-            let mut n = 0;
+                if v == 12 {
+                    if D::COUNT == 3 {
+                        x = 0;
+                        y = 0;
+                        z += 1;
+                    }
 
-            for y in 0..n_lines as i32 {
-                if space.get(Point { x, y }) != SPACE {
-                    n += 1;
+                    continue;
                 }
+
+                space.tree.set(x, y, z, v);
+                space.bounds.read_cell(Point { x, y, z }, v);
+                x += 1;
             }
 
-            space.bounds.set_x(x, n);
+            y += 1;
         }
 
         space.bounds.set_min_max();
@@ -89,8 +111,8 @@ impl Space {
     ///
     /// [`Value`]: ../type.Value.html
     /// [`Point`]: ../struct.Point.html
-    pub(crate) fn get(&self, Point { x, y }: Point) -> Value {
-        self.tree.get(x, y)
+    pub(crate) fn get(&self, Point { x, y, z }: Point) -> Value {
+        self.tree.get(x, y, z)
     }
 
     /// Puts the [`Value`] at the specified [`Point`] in the `Space`.
@@ -101,13 +123,100 @@ impl Space {
     ///
     /// [`Value`]: ../type.Value.html
     /// [`Point`]: ../struct.Point.html
-    pub(crate) fn set(&mut self, Point { x, y }: Point, value: Value) {
-        let old = self.tree.set(x, y, value);
-        self.bounds.update(Point { x, y }, old, value);
+    pub(crate) fn set(&mut self, p: Point, value: Value) {
+        if !self.big.is_empty() {
+            self.big.remove(&p);
+        }
+
+        let old = self.tree.set(p.x, p.y, p.z, value);
+        self.bounds.update(p, old, value);
     }
 
-    /// Returns the northwest corner `(x, y)` of the bounding box of the
-    /// programs source code.
+    /// Retrieves the [`Cell`] stored at the given [`Point`] in the `Space`.
+    ///
+    /// Unlike [`get`], this does not wrap around numbers that are too large for
+    /// a [`Value`].
+    ///
+    /// [`Cell`]: ../cell/enum.Cell.html
+    /// [`Point`]: ../struct.Point.html
+    /// [`get`]: #method.get
+    /// [`Value`]: ../type.Value.html
+    pub(crate) fn get_cell(&self, p: Point) -> Cell {
+        match self.big.get(&p) {
+            Some(cell) => cell.clone(),
+            None       => Cell::Small(self.get(p)),
+        }
+    }
+
+    /// Puts the [`Cell`] at the specified [`Point`] in the `Space`.
+    ///
+    /// [`Cell`]: ../cell/enum.Cell.html
+    /// [`Point`]: ../struct.Point.html
+    pub(crate) fn set_cell(&mut self, p: Point, cell: Cell) {
+        self.set(p, cell.value());
+
+        if let Cell::Big(_) = cell {
+            self.big.insert(p, cell);
+        }
+    }
+
+    /// Retrieves the [`Value`]s in the rectangle with the northwest corner
+    /// `min` and the given `size`, row by row.
+    ///
+    /// The rectangle lies in the plane of `min`; the z component of `size` is
+    /// ignored. A rectangle with a negative width or height is empty.
+    ///
+    /// [`Value`]: ../type.Value.html
+    pub(crate) fn get_rect(&self, min: Point, Delta { dx, dy, .. }: Delta) -> Vec<Value> {
+        let (w, h) = (dx.max(0) as usize, dy.max(0) as usize);
+        let mut values = vec![SPACE; w * h];
+
+        if w > 0 {
+            for (y, row) in values.chunks_mut(w).enumerate() {
+                self.tree.get_row(min.x, min.y.wrapping_add(y as i32), min.z, row);
+            }
+        }
+
+        values
+    }
+
+    /// Puts the [`Value`]s row by row into the rectangle with the northwest
+    /// corner `min` and the given `size`.
+    ///
+    /// The rectangle lies in the plane of `min`. Surplus values are ignored.
+    /// The bounding box is only recomputed once after all cells have been
+    /// written.
+    ///
+    /// [`Value`]: ../type.Value.html
+    pub(crate) fn set_rect(&mut self, min: Point, Delta { dx, dy, .. }: Delta, values: &[Value]) {
+        let (w, h) = (dx.max(0) as usize, dy.max(0) as usize);
+
+        if w == 0 {
+            return;
+        }
+
+        let mut batch = self.batch();
+
+        for (i, &value) in values.iter().take(w * h).enumerate() {
+            let x = min.x.wrapping_add((i % w) as i32);
+            let y = min.y.wrapping_add((i / w) as i32);
+
+            batch.set(Point { x, y, z: min.z }, value);
+        }
+    }
+
+    /// Starts a [`Batch`] of writes to the `Space`.
+    ///
+    /// [`Batch`]: struct.Batch.html
+    pub(crate) fn batch(&mut self) -> Batch<'_, D> {
+        Batch {
+            space: self,
+            changed: false,
+        }
+    }
+
+    /// Returns the least point of the bounding box of the program's source
+    /// code.
     ///
     /// The bounds are updated whenever a [`Value`] other than 32 (space) is
     /// written to a [`Point`] outside the current bounding box.
@@ -117,12 +226,12 @@ impl Space {
     ///
     /// [`Value`]: ../type.Value.html
     /// [`Point`]: ../struct.Point.html
-    pub(crate) fn min(&self) -> (i32, i32) {
-        self.bounds.min()
+    pub(crate) fn min(&self) -> Point {
+        self.bounds.min
     }
 
-    /// Returns the southeast corner `(x, y)` of the bounding box of the
-    /// programs source code.
+    /// Returns the greatest point of the bounding box of the program's source
+    /// code.
     ///
     /// The bounds are updated whenever a [`Value`] other than 32 (space) is
     /// written to a [`Point`] outside the current bounding box.
@@ -132,8 +241,8 @@ impl Space {
     ///
     /// [`Value`]: ../type.Value.html
     /// [`Point`]: ../struct.Point.html
-    pub(crate) fn max(&self) -> (i32, i32) {
-        self.bounds.max()
+    pub(crate) fn max(&self) -> Point {
+        self.bounds.max
     }
 
     /// Advances the [`Point`] `p` by the [`Delta`] `d`, potentially wrapping to
@@ -144,134 +253,156 @@ impl Space {
     ///
     /// [`Point`]: ../struct.Point.html
     /// [`Delta`]: ../struct.Delta.html
-    pub(crate) fn new_position(&self, Point { x, y }: Point, Delta { dx, dy }: Delta) -> Point {
-        use std::cmp::min;
-
-        let (min_x, min_y) = self.bounds.min();
-        let (max_x, max_y) = self.bounds.max();
-
-        let (last_x, sx) = if dx >= 0 {
-            (x > max_x - dx, x - min_x)
-        } else {
-            (x < min_x - dx, x - max_x)
-        };
-
-        let (last_y, sy) = if dy >= 0 {
-            (y > max_y - dy, y - min_y)
-        } else {
-            (y < min_y - dy, y - max_y)
-        };
-
-        if last_x || last_y {
-            let nx = if dx == 0 {
-                i32::max_value()
-            } else {
-                sx / dx
-            };
-            let ny = if dy == 0 {
-                i32::max_value()
-            } else {
-                sy / dy
-            };
-            let n = min(nx, ny);
-
-            Point { x, y } - Delta { dx, dy } * n
+    pub(crate) fn new_position(&self, p: Point, d: Delta) -> Point {
+        if self.is_last(p, d) {
+            let n = self.axes(p, d)
+                .iter()
+                .map(|&(x, dx, min, max)| {
+                    if dx == 0 {
+                        i32::MAX
+                    } else if dx > 0 {
+                        (x - min) / dx
+                    } else {
+                        (x - max) / dx
+                    }
+                })
+                .min()
+                .unwrap();
+
+            p - d * n
         } else {
-            Point { x, y } + Delta { dx, dy }
+            p + d
         }
     }
 
     /// Checks whether adding the [`Delta`] to the [`Point`] would be outside
     /// the bounding box.
-    pub(crate) fn is_last(&self, Point { x, y }: Point, Delta { dx, dy }: Delta) -> bool {
-        let (min_x, min_y) = self.bounds.min();
-        let (max_x, max_y) = self.bounds.max();
-
-        let last_x = if dx >= 0 {
-            x > max_x - dx
-        } else {
-            x < min_x - dx
-        };
+    pub(crate) fn is_last(&self, p: Point, d: Delta) -> bool {
+        self.axes(p, d).iter().any(|&(x, dx, min, max)| {
+            if dx >= 0 {
+                x > max - dx
+            } else {
+                x < min - dx
+            }
+        })
+    }
 
-        let last_y = if dy >= 0 {
-            y > max_y - dy
-        } else {
-            y < min_y - dy
-        };
+    /// Returns the coordinate of `p`, the component of `d` and the bounds of
+    /// the `Space` along each axis.
+    fn axes(&self, p: Point, d: Delta) -> [(i32, i32, i32, i32); 3] {
+        let Bounds { min, max, .. } = self.bounds;
 
-        last_x || last_y
+        [
+            (p.x, d.dx, min.x, max.x),
+            (p.y, d.dy, min.y, max.y),
+            (p.z, d.dz, min.z, max.z),
+        ]
     }
+}
 
-    fn set_line(&mut self, y: i32, line: &str) -> u32 {
-        let mut l = 0;
-
-        let f = |c| {
-            let v = c as i32;
-            if v == 12 {
-                None
-            } else {
-                l += 1;
-                Some(v)
-            }
-        };
+/// A sequence of writes to a [`Space`], for writing many cells at once.
+///
+/// Cells are written immediately, but the bounding box is only recomputed once
+/// the `Batch` is dropped.
+///
+/// [`Space`]: struct.Space.html
+pub(crate) struct Batch<'a, D: Dimension> {
+    space: &'a mut Space<D>,
+    changed: bool,
+}
 
-        let mut n = 0;
+impl<'a, D: Dimension> Batch<'a, D> {
+    /// Retrieves the [`Value`] stored at the given [`Point`] in the [`Space`].
+    ///
+    /// [`Value`]: ../type.Value.html
+    /// [`Point`]: ../struct.Point.html
+    /// [`Space`]: struct.Space.html
+    pub(crate) fn get(&self, p: Point) -> Value {
+        self.space.get(p)
+    }
 
-        for (x, v) in line.chars().filter_map(f).enumerate() {
-            self.tree.set(x as i32, y, v);
+    /// Puts the [`Value`] at the specified [`Point`] in the [`Space`].
+    ///
+    /// [`Value`]: ../type.Value.html
+    /// [`Point`]: ../struct.Point.html
+    /// [`Space`]: struct.Space.html
+    pub(crate) fn set(&mut self, p: Point, value: Value) {
+        let space = &mut *self.space;
 
-            if v != SPACE {
-                n += 1;
-            }
+        if !space.big.is_empty() {
+            space.big.remove(&p);
         }
 
-        self.bounds.set_y(y, n);
+        let old = space.tree.set(p.x, p.y, p.z, value);
+
+        self.changed |= space.bounds.count(p, old, value);
+    }
+}
 
-        l
+impl<'a, D: Dimension> Drop for Batch<'a, D> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.space.bounds.set_min_max();
+        }
     }
 }
 
 #[derive(Clone)]
 struct Bounds {
-    min_x: i32,
-    min_y: i32,
-    max_x: i32,
-    max_y: i32,
+    min: Point,
+    max: Point,
     nonempty_x: BTreeMap<i32, u32>,
     nonempty_y: BTreeMap<i32, u32>,
+    nonempty_z: BTreeMap<i32, u32>,
 }
 
 impl Bounds {
     fn new() -> Bounds {
         Bounds {
-            min_x: 0,
-            min_y: 0,
-            max_x: 0,
-            max_y: 0,
+            min: Point { x: 0, y: 0, z: 0 },
+            max: Point { x: 0, y: 0, z: 0 },
             nonempty_x: BTreeMap::new(),
             nonempty_y: BTreeMap::new(),
+            nonempty_z: BTreeMap::new(),
         }
     }
 
-    fn set_x(&mut self, x: i32, n: u32) {
+    /// Counts a cell of the source code, without recomputing the bounding box.
+    fn read_cell(&mut self, Point { x, y, z }: Point, v: Value) {
+        let n = if v == SPACE {
+            0
+        } else {
+            1
+        };
+
         *self.nonempty_x.entry(x).or_insert(0) += n;
+        *self.nonempty_y.entry(y).or_insert(0) += n;
+        *self.nonempty_z.entry(z).or_insert(0) += n;
     }
 
-    fn set_y(&mut self, y: i32, n: u32) {
-        *self.nonempty_y.entry(y).or_insert(0) += n;
+    fn update(&mut self, p: Point, old: Value, new: Value) {
+        if self.count(p, old, new) {
+            self.set_min_max();
+        }
     }
 
-    fn update(&mut self, Point { x, y }: Point, old: Value, new: Value) {
+    /// Updates the number of nonempty cells without recomputing the bounding
+    /// box. Returns `true` if the numbers have changed.
+    fn count(&mut self, Point { x, y, z }: Point, old: Value, new: Value) -> bool {
         if old == SPACE && new != SPACE {
             *self.nonempty_x.entry(x).or_insert(0) += 1;
             *self.nonempty_y.entry(y).or_insert(0) += 1;
+            *self.nonempty_z.entry(z).or_insert(0) += 1;
 
-            self.set_min_max();
+            true
         } else if old != SPACE && new == SPACE {
             self.nonempty_x.entry(x).and_modify(|r| *r -= 1);
             self.nonempty_y.entry(y).and_modify(|r| *r -= 1);
+            self.nonempty_z.entry(z).and_modify(|r| *r -= 1);
 
-            self.set_min_max();
+            true
+        } else {
+            false
         }
     }
 
@@ -284,46 +415,41 @@ impl Bounds {
             }
         };
 
-        self.min_x = self.nonempty_x.iter().filter_map(f).next().unwrap_or(0);
-        self.min_y = self.nonempty_y.iter().filter_map(f).next().unwrap_or(0);
-        self.max_x = self.nonempty_x.iter().filter_map(f).next_back().unwrap_or(0);
-        self.max_y = self.nonempty_y.iter().filter_map(f).next_back().unwrap_or(0);
-    }
-
-    fn min(&self) -> (i32, i32) {
-        (self.min_x, self.min_y)
-    }
-
-    fn max(&self) -> (i32, i32) {
-        (self.max_x, self.max_y)
+        self.min.x = self.nonempty_x.iter().filter_map(f).next().unwrap_or(0);
+        self.min.y = self.nonempty_y.iter().filter_map(f).next().unwrap_or(0);
+        self.min.z = self.nonempty_z.iter().filter_map(f).next().unwrap_or(0);
+        self.max.x = self.nonempty_x.iter().filter_map(f).next_back().unwrap_or(0);
+        self.max.y = self.nonempty_y.iter().filter_map(f).next_back().unwrap_or(0);
+        self.max.z = self.nonempty_z.iter().filter_map(f).next_back().unwrap_or(0);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::{Befunge, Trefunge};
 
     #[test]
     fn space_get_uninit() {
-        let space = Space::new();
+        let space = Space::<Befunge>::new();
 
-        assert_eq!(SPACE, space.get(Point { x: 0, y: 0 }));
+        assert_eq!(SPACE, space.get(Point { x: 0, y: 0, z: 0 }));
     }
 
     #[test]
     fn space_get_empty() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
-        space.set(Point { x: 0, y: 0 }, 40);
+        space.set(Point { x: 0, y: 0, z: 0 }, 40);
 
-        assert_eq!(SPACE, space.get(Point { x: 1, y: 0 }));
+        assert_eq!(SPACE, space.get(Point { x: 1, y: 0, z: 0 }));
     }
 
     #[test]
     fn space_set_get() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
-        let position = Point { x: 3, y: 6 };
+        let position = Point { x: 3, y: 6, z: 0 };
         let value = 45;
 
         space.set(position, value);
@@ -333,9 +459,9 @@ mod tests {
 
     #[test]
     fn space_set_get_large() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
-        let position = Point { x: 2147483647, y: -1029771328 };
+        let position = Point { x: 2147483647, y: -1029771328, z: 0 };
         let value = 1307812;
 
         space.set(position, value);
@@ -345,13 +471,13 @@ mod tests {
 
     #[test]
     fn space_set_get_multiple() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
         let data = [
-            (Point { x:  0, y:  0 },  12),
-            (Point { x:  3, y:  2 },   0),
-            (Point { x: -2, y: -1 }, -42),
-            (Point { x:  1, y: -3 },   6),
+            (Point { x:  0, y:  0, z: 0 },  12),
+            (Point { x:  3, y:  2, z: 0 },   0),
+            (Point { x: -2, y: -1, z: 0 }, -42),
+            (Point { x:  1, y: -3, z: 0 },   6),
         ];
 
         for &(p, v) in data.iter() {
@@ -363,61 +489,110 @@ mod tests {
         }
     }
 
+    #[test]
+    fn space_get_outside_tree() {
+        let mut space = Space::<Befunge>::new();
+
+        space.set(Point { x: 1, y: 0, z: 0 }, 42);
+
+        assert_eq!(SPACE, space.get(Point { x: 17, y: 0, z: 0 }));
+        assert_eq!(SPACE, space.get(Point { x: 1, y: -4096, z: 0 }));
+    }
+
+    #[test]
+    fn space_rect() {
+        let mut space = Space::<Befunge>::new();
+
+        let min = Point { x: -3, y: 14, z: 0 };
+        let size = Delta { dx: 40, dy: 3, dz: 0 };
+        let values: Vec<_> = (0..120).collect();
+
+        space.set_rect(min, size, &values);
+
+        assert_eq!(values, space.get_rect(min, size));
+        assert_eq!(39, space.get(Point { x: 36, y: 14, z: 0 }));
+        assert_eq!(40, space.get(Point { x: -3, y: 15, z: 0 }));
+        assert_eq!(Point { x: -3, y: 14, z: 0 }, space.min());
+        assert_eq!(Point { x: 36, y: 16, z: 0 }, space.max());
+        assert_eq!(
+            vec![SPACE, 0, 1, SPACE, 40, 41],
+            space.get_rect(Point { x: -4, y: 14, z: 0 }, Delta { dx: 3, dy: 2, dz: 0 })
+        );
+    }
+
+    #[test]
+    fn space_batch() {
+        let mut space = Space::<Befunge>::new();
+
+        {
+            let mut batch = space.batch();
+
+            batch.set(Point { x: 5, y: -2, z: 0 }, 70);
+            batch.set(Point { x: -1, y: 3, z: 0 }, 71);
+
+            assert_eq!(70, batch.get(Point { x: 5, y: -2, z: 0 }));
+        }
+
+        assert_eq!(71, space.get(Point { x: -1, y: 3, z: 0 }));
+        assert_eq!(Point { x: -1, y: -2, z: 0 }, space.min());
+        assert_eq!(Point { x: 5, y: 3, z: 0 }, space.max());
+    }
+
     #[test]
     fn space_init_bounds() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
         let (x, y) = (2, -3);
 
-        space.set(Point { x, y }, 12);
+        space.set(Point { x, y, z: 0 }, 12);
 
-        assert_eq!((x, y), space.min());
-        assert_eq!((x, y), space.max());
+        assert_eq!(Point { x, y, z: 0 }, space.min());
+        assert_eq!(Point { x, y, z: 0 }, space.max());
     }
 
     #[test]
     fn space_grow_bounds() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
-        space.set(Point { x: 0, y: 0 }, 42);
+        space.set(Point { x: 0, y: 0, z: 0 }, 42);
 
         let (x0, y0) = (-3, 5);
         let (x1, y1) = (2, -1);
 
-        space.set(Point { x: x0, y: y0 }, 1);
-        space.set(Point { x: x1, y: y1 }, 2);
+        space.set(Point { x: x0, y: y0, z: 0 }, 1);
+        space.set(Point { x: x1, y: y1, z: 0 }, 2);
 
-        assert_eq!((-3, -1), space.min());
-        assert_eq!((2, 5), space.max());
+        assert_eq!(Point { x: -3, y: -1, z: 0 }, space.min());
+        assert_eq!(Point { x: 2, y: 5, z: 0 }, space.max());
     }
 
     #[test]
     fn space_keep_bounds() {
-        let mut space = Space::new();
+        let mut space = Space::<Befunge>::new();
 
-        space.set(Point { x: 0, y: 0 }, 42);
-        space.set(Point { x: -2, y: 3 }, SPACE);
+        space.set(Point { x: 0, y: 0, z: 0 }, 42);
+        space.set(Point { x: -2, y: 3, z: 0 }, SPACE);
 
-        assert_eq!((0, 0), space.min());
-        assert_eq!((0, 0), space.max());
+        assert_eq!(Point { x: 0, y: 0, z: 0 }, space.min());
+        assert_eq!(Point { x: 0, y: 0, z: 0 }, space.max());
     }
 
     #[test]
     fn space_read() {
         let code = "123\n456\n789";
-        let space = Space::read(code);
+        let space = Space::<Befunge>::read(code);
 
         for i in 0..9 {
-            assert_eq!(i + '1' as i32, space.get(Point { x: i % 3, y: i / 3 }));
+            assert_eq!(Value::from(i) + '1' as Value, space.get(Point { x: i % 3, y: i / 3, z: 0 }));
         }
 
-        assert_eq!((2, 2), space.max());
+        assert_eq!(Point { x: 2, y: 2, z: 0 }, space.max());
     }
 
     #[test]
     fn space_read_bounds() {
         let code = " a  b\nc d\n e";
-        let space = Space::read(code);
+        let space = Space::<Befunge>::read(code);
 
         let nx: Vec<_> = space.bounds.nonempty_x.iter().collect();
         let ny: Vec<_> = space.bounds.nonempty_y.iter().collect();
@@ -425,4 +600,21 @@ mod tests {
         assert_eq!(&[(&0, &1), (&1, &2), (&2, &1), (&3, &0), (&4, &1)], &nx[..]);
         assert_eq!(&[(&0, &2), (&1, &2), (&2, &1)], &ny[..]);
     }
+
+    #[test]
+    fn space_read_planes() {
+        let code = "ab\x0ccd\ne\x0c\x0cf";
+        let space = Space::<Trefunge>::read(code);
+
+        assert_eq!('b' as Value, space.get(Point { x: 1, y: 0, z: 0 }));
+        assert_eq!('c' as Value, space.get(Point { x: 0, y: 0, z: 1 }));
+        assert_eq!('e' as Value, space.get(Point { x: 0, y: 1, z: 1 }));
+        assert_eq!('f' as Value, space.get(Point { x: 0, y: 0, z: 3 }));
+        assert_eq!(Point { x: 1, y: 1, z: 3 }, space.max());
+
+        let space = Space::<Befunge>::read(code);
+
+        assert_eq!('c' as Value, space.get(Point { x: 2, y: 0, z: 0 }));
+        assert_eq!(Point { x: 3, y: 1, z: 0 }, space.max());
+    }
 }
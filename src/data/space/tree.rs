@@ -15,6 +15,8 @@
 // You should have received a copy of the GNU General Public License
 // along with mycon.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use crate::data::{Value, SPACE};
 
 const CHUNK_SHIFT: u32 = 4;
@@ -58,6 +60,7 @@ pub(super) enum FungeTree {
 pub(super) trait Tree: Default {
     fn get(&self, x: i32, y: i32) -> Value;
     fn set(&mut self, x: i32, y: i32, value: Value) -> Value;
+    fn chunk(&self, x: i32, y: i32) -> Option<&Chunk>;
 
 //    fn get_chunk(&self, x: i32, y: i32) -> Chunk;
 //    fn set_chunk(&mut self, x: i32, y: i32, chunk: Chunk);
@@ -87,6 +90,10 @@ impl Tree for Chunk {
         old
     }
 
+    fn chunk(&self, _: i32, _: i32) -> Option<&Chunk> {
+        Some(self)
+    }
+
 //    fn get_chunk(&self, _: i32, _: i32) -> Chunk {
 //        self.clone()
 //    }
@@ -153,6 +160,16 @@ impl<T: Tree> Tree for Node<T> {
         old
     }
 
+    fn chunk(&self, x: i32, y: i32) -> Option<&Chunk> {
+        let (i, j) = get_indices(x, y);
+        let (x, y) = shift(x, y);
+
+        match &self.data[i][j] {
+            Some(tree) => tree.chunk(x, y),
+            None       => None,
+        }
+    }
+
 //    fn get_chunk(&self, x: i32, y: i32) -> Chunk {
 //        let (i, j) = get_indices(x, y);
 //        let (x, y) = shift(x, y);
@@ -204,6 +221,19 @@ impl<T: Tree> Tree for Node<T> {
 //    }
 }
 
+/// A three-dimensional space, made up of one [`FungeTree`] for every plane that
+/// contains nonempty cells.
+///
+/// Plane 0, the only one used outside of Trefunge, is kept apart from the
+/// others, so that accessing it needs no additional lookup.
+///
+/// [`FungeTree`]: enum.FungeTree.html
+#[derive(Clone, Default)]
+pub(super) struct PlaneTree {
+    origin: FungeTree,
+    planes: BTreeMap<i32, FungeTree>,
+}
+
 impl Default for FungeTree {
     fn default() -> Self {
         FungeTree::Depth0(Chunk::default())
@@ -212,12 +242,15 @@ impl Default for FungeTree {
 
 macro_rules! get_case {
     ($t:ident, $x:ident, $y:ident, $d:literal) => {
+        get_case!($t, get, $x, $y, $d)
+    };
+    ($t:ident, $f:ident, $x:ident, $y:ident, $d:literal) => {
         {
             let shift = (7 - $d) * CHUNK_SHIFT;
 
-            $t.get($x << shift, $y << shift)
+            $t.$f($x << shift, $y << shift)
         }
-    }
+    };
 }
 
 macro_rules! set_case {
@@ -254,6 +287,10 @@ impl Tree for FungeTree {
 
         let (x, y) = offset(x, y);
 
+        if !self.covers(x, y) {
+            return SPACE;
+        }
+
         match self {
             Depth0(t) => get_case!(t, x, y, 0),
             Depth1(t) => get_case!(t, x, y, 1),
@@ -271,9 +308,75 @@ impl Tree for FungeTree {
 
         self.set_rec(x, y, value)
     }
+
+    fn chunk(&self, x: i32, y: i32) -> Option<&Chunk> {
+        use FungeTree::*;
+
+        let (x, y) = offset(x, y);
+
+        if !self.covers(x, y) {
+            return None;
+        }
+
+        match self {
+            Depth0(t) => Some(t),
+            Depth1(t) => get_case!(t, chunk, x, y, 1),
+            Depth2(t) => get_case!(t, chunk, x, y, 2),
+            Depth3(t) => get_case!(t, chunk, x, y, 3),
+            Depth4(t) => get_case!(t, chunk, x, y, 4),
+            Depth5(t) => get_case!(t, chunk, x, y, 5),
+            Depth6(t) => get_case!(t, chunk, x, y, 6),
+            Depth7(t) => get_case!(t, chunk, x, y, 7),
+        }
+    }
 }
 
 impl FungeTree {
+    /// Reads `row.len()` consecutive cells starting at `(x, y)` into `row`.
+    ///
+    /// The tree is only traversed once for every chunk the row intersects.
+    pub(super) fn get_row(&self, x: i32, y: i32, row: &mut [Value]) {
+        let mut i = 0;
+
+        while i < row.len() {
+            let x = x.wrapping_add(i as i32);
+            let (cx, cy) = local_indices(offset(x, y));
+            let n = (CHUNK_SIZE - cx).min(row.len() - i);
+
+            match self.chunk(x, y) {
+                Some(chunk) => for (k, v) in row[i..i + n].iter_mut().enumerate() {
+                    *v = chunk.data[cx + k][cy];
+                },
+                None        => for v in &mut row[i..i + n] {
+                    *v = SPACE;
+                },
+            }
+
+            i += n;
+        }
+    }
+
+    /// Checks whether the offset coordinates `(x, y)` lie in the region
+    /// currently represented by the tree.
+    fn covers(&self, x: i32, y: i32) -> bool {
+        use FungeTree::*;
+
+        let depth = match self {
+            Depth0(_) => 0,
+            Depth1(_) => 1,
+            Depth2(_) => 2,
+            Depth3(_) => 3,
+            Depth4(_) => 4,
+            Depth5(_) => 5,
+            Depth6(_) => 6,
+            Depth7(_) => return true,
+        };
+        let shift = (depth + 1) * CHUNK_SHIFT;
+        let ix = OFFSET >> shift;
+
+        x >> shift == ix && y >> shift == ix
+    }
+
     fn set_rec(&mut self, x: i32, y: i32, value: Value) -> Value {
         use FungeTree::*;
 
@@ -290,11 +393,54 @@ impl FungeTree {
     }
 }
 
+impl PlaneTree {
+    pub(super) fn get(&self, x: i32, y: i32, z: i32) -> Value {
+        match self.plane(z) {
+            Some(tree) => tree.get(x, y),
+            None       => SPACE,
+        }
+    }
+
+    pub(super) fn set(&mut self, x: i32, y: i32, z: i32, value: Value) -> Value {
+        if z == 0 {
+            return self.origin.set(x, y, value);
+        }
+
+        if value == SPACE && !self.planes.contains_key(&z) {
+            return SPACE;
+        }
+
+        self.planes.entry(z).or_default().set(x, y, value)
+    }
+
+    /// Reads `row.len()` consecutive cells starting at `(x, y, z)` into `row`.
+    pub(super) fn get_row(&self, x: i32, y: i32, z: i32, row: &mut [Value]) {
+        match self.plane(z) {
+            Some(tree) => tree.get_row(x, y, row),
+            None       => for v in row {
+                *v = SPACE;
+            },
+        }
+    }
+
+    fn plane(&self, z: i32) -> Option<&FungeTree> {
+        if z == 0 {
+            Some(&self.origin)
+        } else {
+            self.planes.get(&z)
+        }
+    }
+}
+
 fn get_indices(x: i32, y: i32) -> (usize, usize) {
     ((x as usize & CHUNK_MASK) >> CHUNK_SHIFT_BACK,
     (y as usize & CHUNK_MASK) >> CHUNK_SHIFT_BACK)
 }
 
+fn local_indices((x, y): (i32, i32)) -> (usize, usize) {
+    (x as usize & (CHUNK_SIZE - 1), y as usize & (CHUNK_SIZE - 1))
+}
+
 fn shift(x: i32, y: i32) -> (i32, i32) {
     (x << CHUNK_SHIFT, y << CHUNK_SHIFT)
 }
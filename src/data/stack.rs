@@ -19,30 +19,39 @@
 //!
 //! [`Ip`]: ../../program/ip/struct.Ip.html
 
+use std::collections::VecDeque;
 use std::fmt;
 
-use super::{Value, Point};
+use super::{Dimension, Value, Point, Delta, to_char};
+use super::cell::Cell;
 
-type Stack = Vec<Value>;
+type Stack = VecDeque<Cell>;
 
 /// The stack stack of an [`Ip`].
 ///
-/// This is simply a stack of stacks, each stack storing [`Value`]s. The stack
+/// This is simply a stack of stacks, each stack storing [`Cell`]s. The stack
 /// stack always contains at least a single stack, though the individual stacks
 /// may be empty.
 ///
-/// [`Value`]: ../type.Value.html
+/// In invert mode, [`Cell`]s are pushed to the bottom of the top stack instead
+/// of its top. In queue mode, they are popped off its bottom.
+///
+/// [`Cell`]: ../cell/enum.Cell.html
 /// [`Ip`]: ../../program/ip/struct.Ip.html
 #[derive(Clone, Debug)]
 pub(crate) struct StackStack {
     stacks: Vec<Stack>,
+    invert: bool,
+    queue: bool,
 }
 
 impl StackStack {
     /// Creates a new `StackStack` containing a single empty stack.
     pub(crate) fn new() -> Self {
         StackStack {
-            stacks: vec![Vec::new()],
+            stacks: vec![VecDeque::new()],
+            invert: false,
+            queue: false,
         }
     }
 
@@ -67,7 +76,40 @@ impl StackStack {
     ///
     /// [`Value`]: ../type.Value.html
     pub(crate) fn push(&mut self, value: Value) {
-        self.top().push(value);
+        self.push_cell(Cell::Small(value));
+    }
+
+    /// Pushes a [`Cell`] to the top stack on the `StackStack`.
+    ///
+    /// [`Cell`]: ../cell/enum.Cell.html
+    pub(crate) fn push_cell(&mut self, cell: Cell) {
+        if self.invert {
+            self.top().push_front(cell);
+        } else {
+            self.top().push_back(cell);
+        }
+    }
+
+    /// Pushes the components of a vector along each dimension of `D` to the top
+    /// stack on the `StackStack`, the last one on top.
+    pub(crate) fn push_vector<D: Dimension>(&mut self, Delta { dx, dy, dz }: Delta) {
+        for &v in &[dx, dy, dz][..D::COUNT] {
+            self.push(Value::from(v));
+        }
+    }
+
+    /// Pops a vector with a component for each dimension of `D` off the top
+    /// stack on the `StackStack`, the last component first.
+    ///
+    /// The remaining components are `0`.
+    pub(crate) fn pop_vector<D: Dimension>(&mut self) -> Delta {
+        let mut v = [0; 3];
+
+        for c in v[..D::COUNT].iter_mut().rev() {
+            *c = self.pop() as i32;
+        }
+
+        Delta { dx: v[0], dy: v[1], dz: v[2] }
     }
 
     /// Pushes a string to the top stack on the `StackStack`.
@@ -80,45 +122,126 @@ impl StackStack {
         let mut n = 1;
 
         self.push(0);
-        self.top().append(&mut s.chars()
-                          .rev()
-                          .map(|c| {
-                              n += 1;
-                              c as i32
-                          })
-                          .collect());
+
+        for c in s.chars().rev() {
+            self.push(c as Value);
+            n += 1;
+        }
 
         n
     }
 
     /// Pops a [`Value`] from the top stack on the `StackStack`.
     ///
-    /// If the top stack is empty, `0` will be returned.
+    /// If the top stack is empty, `0` will be returned. Numbers too large for a
+    /// [`Value`] wrap around.
     ///
     /// [`Value`]: ../type.Value.html
     pub(crate) fn pop(&mut self) -> Value {
-        let top = self.top();
+        self.pop_cell().value()
+    }
 
-        match top.pop() {
-            Some(v) => v,
-            None    => 0,
+    /// Pops a [`Cell`] from the top stack on the `StackStack`.
+    ///
+    /// If the top stack is empty, `0` will be returned.
+    ///
+    /// [`Cell`]: ../cell/enum.Cell.html
+    pub(crate) fn pop_cell(&mut self) -> Cell {
+        if self.queue {
+            self.top().pop_front().unwrap_or_default()
+        } else {
+            self.top().pop_back().unwrap_or_default()
         }
     }
 
     /// Returns the `n`th cell of the top stack, counted from the top.
     ///
     /// If `n` is out of bounds, 0 will be returned.
-    pub(crate) fn nth(&mut self, n: usize) -> Value {
+    pub(crate) fn nth(&mut self, n: usize) -> Cell {
         let top = self.top();
         let len = top.len();
 
         if n >= len {
-            0
+            Cell::default()
         } else {
-            top[len - n]
+            top[len - n].clone()
         }
     }
 
+    /// Tries to reserve memory for `n` more cells on the top stack.
+    ///
+    /// Returns `false` if the memory could not be allocated.
+    pub(crate) fn reserve(&mut self, n: usize) -> bool {
+        self.top().try_reserve(n).is_ok()
+    }
+
+    /// Returns the number of cells on the top stack.
+    pub(crate) fn len(&self) -> usize {
+        self.stacks.last().map_or(0, VecDeque::len)
+    }
+
+    /// Returns the `n`th cell of the top stack, counted from the top starting
+    /// at 0.
+    ///
+    /// If `n` is out of bounds, 0 will be returned.
+    pub(crate) fn peek(&self, n: usize) -> Cell {
+        let top = &self.stacks[self.stacks.len() - 1];
+
+        match top.len().checked_sub(n + 1) {
+            Some(i) => top[i].clone(),
+            None    => Cell::default(),
+        }
+    }
+
+    /// Removes the `n`th cell of the top stack, counted from the top starting
+    /// at 0, and returns it.
+    ///
+    /// If `n` is out of bounds, nothing is removed and 0 will be returned.
+    pub(crate) fn remove(&mut self, n: usize) -> Cell {
+        let top = self.top();
+
+        match top.len().checked_sub(n + 1) {
+            Some(i) => top.remove(i).unwrap_or_default(),
+            None    => Cell::default(),
+        }
+    }
+
+    /// Inserts a [`Cell`] into the top stack so that it becomes the `n`th
+    /// cell, counted from the top starting at 0.
+    ///
+    /// If `n` exceeds the size of the stack, the stack is filled up with zeros
+    /// from the bottom.
+    ///
+    /// [`Cell`]: ../cell/enum.Cell.html
+    pub(crate) fn insert(&mut self, n: usize, cell: Cell) {
+        let top = self.top();
+
+        while top.len() < n {
+            top.push_front(Cell::default());
+        }
+
+        let i = top.len() - n;
+
+        top.insert(i, cell);
+    }
+
+    /// Moves the top `n` cells of the top stack beneath the `k` cells below
+    /// them, keeping the order within both groups.
+    ///
+    /// If the stack holds fewer than `n + k` cells, it is filled up with zeros
+    /// from the bottom.
+    pub(crate) fn rotate(&mut self, n: usize, k: usize) {
+        let top = self.top();
+
+        while top.len() < n + k {
+            top.push_front(Cell::default());
+        }
+
+        let len = top.len();
+
+        top.make_contiguous()[len - n - k ..].rotate_right(n);
+    }
+
     /// Tries to pop a string from the top stack on the `StackStack`.
     ///
     /// It will be popped character by character, until a 0 is encountered.
@@ -133,7 +256,7 @@ impl StackStack {
                 break;
             }
 
-            if let Some(c) = std::char::from_u32(v as u32) {
+            if let Some(c) = to_char(v) {
                 s.push(c);
             } else {
                 return None;
@@ -152,7 +275,19 @@ impl StackStack {
     ///
     /// The first element is the size of the bottommost stack.
     pub(crate) fn stack_sizes(&self) -> Vec<usize> {
-        self.stacks.iter().map(Vec::len).collect()
+        self.stacks.iter().map(VecDeque::len).collect()
+    }
+
+    /// Toggles invert mode, in which values are pushed to the bottom of the top
+    /// stack.
+    pub(crate) fn toggle_invert_mode(&mut self) {
+        self.invert = !self.invert;
+    }
+
+    /// Toggles queue mode, in which values are popped off the bottom of the top
+    /// stack.
+    pub(crate) fn toggle_queue_mode(&mut self) {
+        self.queue = !self.queue;
     }
 
     /// Deletes `n` cells from the top stack, from the top down.
@@ -170,35 +305,32 @@ impl StackStack {
     /// Pushes a new stack onto the `StackStack`.
     ///
     /// `n` elements from the stack previously on top will be transferred to the
-    /// new stack. Then, the given [`Point`] will be pushed onto the (now)
-    /// second stack.
+    /// new stack. Then, the components of the given storage offset along each
+    /// dimension of `D` will be pushed onto the (now) second stack.
     ///
     /// For details, consult the description of the `{` instruction in the
     /// Funge-98 specification.
-    ///
-    /// [`Point`]: ../struct.Point.html
-    pub(crate) fn create_stack(&mut self, n: i32, Point { x, y }: Point) {
-        let mut new = Vec::new();
+    pub(crate) fn create_stack<D: Dimension>(&mut self, n: Value, Point { x, y, z }: Point) {
+        let mut new = VecDeque::new();
 
         {
             let top = self.top();
             let len = top.len();
 
-            let m = n as u32 as usize;
+            let m = n as usize;
 
             if n > 0 {
                 if m <= len {
                     new.append(&mut top.split_off(len - m));
                 } else {
-                    new.append(&mut vec![0; m - len]);
+                    new.resize(m - len, Cell::default());
                     new.append(&mut top.split_off(len));
                 }
             } else if n < 0 {
-                top.append(&mut vec![0; -n as usize]);
+                top.resize(len + n.unsigned_abs() as usize, Cell::default());
             }
 
-            top.push(x);
-            top.push(y);
+            top.extend([x, y, z][..D::COUNT].iter().map(|&v| Cell::Small(Value::from(v))));
         }
 
         self.stacks.push(new);
@@ -206,9 +338,9 @@ impl StackStack {
 
     /// Deletes the top stack of the `StackStack`.
     ///
-    /// A [`Point`] will be popped off the stack directly below the one to be
-    /// deleted. `n` elements from the deleted stack will be transferred to the
-    /// stack now on top.
+    /// A storage offset with a component for each dimension of `D` will be
+    /// popped off the stack directly below the one to be deleted. `n` elements
+    /// from the deleted stack will be transferred to the stack now on top.
     ///
     /// For details, consult the description of the `}` instruction in the
     /// Funge-98 specification.
@@ -216,9 +348,7 @@ impl StackStack {
     /// # Panics
     ///
     /// Panics if there is only one stack on the `StackStack`.
-    ///
-    /// [`Point`]: ../struct.Point.html
-    pub(crate) fn delete_stack(&mut self, n: i32) -> Point {
+    pub(crate) fn delete_stack<D: Dimension>(&mut self, n: Value) -> Point {
         use std::cmp::min;
 
         assert!(!self.single());
@@ -228,24 +358,27 @@ impl StackStack {
 
         let top = self.top();
 
-        let y = top.pop().unwrap_or(0);
-        let x = top.pop().unwrap_or(0);
+        let mut offset = [0; 3];
 
-        let m = n as u32 as usize;
+        for v in offset[..D::COUNT].iter_mut().rev() {
+            *v = top.pop_back().map_or(0, |c| c.value() as i32);
+        }
+
+        let m = n as usize;
 
         if n > 0 {
             if m <= len {
                 top.append(&mut old.split_off(len - m));
             } else {
-                top.append(&mut vec![0; m - len]);
+                top.resize(top.len() + m - len, Cell::default());
                 top.append(&mut old.split_off(len));
             }
         } else if n < 0 {
             let len = top.len();
-            top.drain(len - min(len, -n as usize) .. len);
+            top.drain(len - min(len, n.unsigned_abs() as usize) .. len);
         }
 
-        Point { x, y }
+        Point { x: offset[0], y: offset[1], z: offset[2] }
     }
 
     /// Transfers `n` elements from the second stack to the top stack.
@@ -258,18 +391,18 @@ impl StackStack {
     /// # Panics
     ///
     /// Panics if there is only one stack on the `StackStack`.
-    pub(crate) fn transfer_elements(&mut self, n: i32) {
+    pub(crate) fn transfer_elements(&mut self, n: Value) {
         assert!(!self.single());
 
         if n > 0 {
             for _ in 0..n {
-                let v = self.second().pop().unwrap_or(0);
-                self.top().push(v);
+                let v = self.second().pop_back().unwrap_or_default();
+                self.top().push_back(v);
             }
         } else if n < 0 {
-            for _ in 0..-n {
-                let v = self.top().pop().unwrap_or(0);
-                self.second().push(v);
+            for _ in 0..n.unsigned_abs() {
+                let v = self.top().pop_back().unwrap_or_default();
+                self.second().push_back(v);
             }
         }
     }
@@ -288,7 +421,10 @@ impl fmt::Display for StackStack {
 
 #[cfg(test)]
 mod tests {
+    use num_bigint::BigInt;
+
     use super::*;
+    use crate::data::{Unefunge, Trefunge};
 
     #[test]
     fn stack_pop_empty() {
@@ -346,4 +482,101 @@ mod tests {
         assert_eq!(1, stack.pop());
         assert_eq!(0, stack.pop());
     }
+
+    #[test]
+    fn stack_random_access() {
+        let mut stack = StackStack::new();
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        assert_eq!(Cell::from(3), stack.peek(0));
+        assert_eq!(Cell::from(1), stack.peek(2));
+        assert_eq!(Cell::from(0), stack.peek(3));
+        assert_eq!(Cell::from(2), stack.remove(1));
+
+        stack.insert(0, Cell::from(4));
+        stack.insert(4, Cell::from(5));
+
+        assert_eq!(4, stack.pop());
+        assert_eq!(3, stack.pop());
+        assert_eq!(1, stack.pop());
+        assert_eq!(0, stack.pop());
+        assert_eq!(5, stack.pop());
+    }
+
+    #[test]
+    fn stack_rotate() {
+        let mut stack = StackStack::new();
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        stack.rotate(1, 2);
+
+        assert_eq!(2, stack.pop());
+        assert_eq!(1, stack.pop());
+        assert_eq!(3, stack.pop());
+
+        stack.push(4);
+        stack.rotate(1, 1);
+
+        assert_eq!(0, stack.pop());
+        assert_eq!(4, stack.pop());
+    }
+
+    #[test]
+    fn stack_vector() {
+        let mut stack = StackStack::new();
+
+        let delta = Delta { dx: 1, dy: 2, dz: 3 };
+
+        stack.push_vector::<Trefunge>(delta);
+
+        assert_eq!(Delta { dx: 3, dy: 0, dz: 0 }, stack.pop_vector::<Unefunge>());
+        assert_eq!(Delta { dx: 0, dy: 1, dz: 2 }, stack.pop_vector::<Trefunge>());
+    }
+
+    #[test]
+    fn stack_big_cell() {
+        let mut stack = StackStack::new();
+
+        let big = Cell::from_big(BigInt::from(Value::MAX) + 1);
+
+        stack.push_cell(big.clone());
+        stack.push_cell(big.clone());
+
+        assert_eq!(big, stack.pop_cell());
+        assert_eq!(Value::MIN, stack.pop());
+    }
+
+    #[test]
+    fn stack_invert_mode() {
+        let mut stack = StackStack::new();
+
+        stack.push(1);
+        stack.toggle_invert_mode();
+        stack.push(2);
+        stack.push(3);
+
+        assert_eq!(1, stack.pop());
+        assert_eq!(2, stack.pop());
+        assert_eq!(3, stack.pop());
+    }
+
+    #[test]
+    fn stack_queue_mode() {
+        let mut stack = StackStack::new();
+
+        stack.push(1);
+        stack.push(2);
+        stack.toggle_queue_mode();
+        stack.push(3);
+
+        assert_eq!(1, stack.pop());
+        assert_eq!(2, stack.pop());
+        assert_eq!(3, stack.pop());
+        assert_eq!(0, stack.pop());
+    }
 }
@@ -27,6 +27,14 @@ mod program;
 
 pub use crate::config::Config;
 pub use crate::config::FileView;
+pub use crate::config::NetworkView;
 pub use crate::config::ExecAction;
 pub use crate::config::Trace;
+pub use crate::data::CellSize;
+pub use crate::data::Overflow;
+pub use crate::data::{Dimension, Unefunge, Befunge, Trefunge};
+pub use crate::data::{Point, Delta};
+pub use crate::program::Fingerprint;
+pub use crate::program::Handle;
+pub use crate::program::OverflowError;
 pub use crate::program::Program;
@@ -62,6 +62,35 @@ fn run() -> i32 {
              .long("sleep")
              .takes_value(true)
              .value_name("time"))
+        .arg(Arg::with_name("UNEFUNGE")
+             .help("interpret the source file as Unefunge-98")
+             .short("1")
+             .long("unefunge")
+             .conflicts_with("TREFUNGE"))
+        .arg(Arg::with_name("TREFUNGE")
+             .help("interpret the source file as Trefunge-98")
+             .short("3")
+             .long("trefunge"))
+        .arg(Arg::with_name("CELL_SIZE")
+             .help("the size of cells in bits")
+             .short("c")
+             .long("cell-size")
+             .takes_value(true)
+             .value_name("size")
+             .possible_values(&["32", "64", "unbounded"]))
+        .arg(Arg::with_name("OVERFLOW")
+             .help("how to handle arithmetic overflow")
+             .short("o")
+             .long("overflow")
+             .takes_value(true)
+             .value_name("mode")
+             .possible_values(&["wrap", "saturate", "trap"]))
+        .arg(Arg::with_name("DRAWING")
+             .help("file to write drawings of the TURT fingerprint to")
+             .short("d")
+             .long("drawing")
+             .takes_value(true)
+             .value_name("file"))
         .get_matches();
 
     let mut timing = if matches.is_present("TIME") {
@@ -107,42 +136,87 @@ fn run() -> i32 {
             });
     }
 
-    let mut prog = Program::read(&code).config(config);
+    match matches.value_of("CELL_SIZE") {
+        Some("64")        => config = config.cell_size(CellSize::Bits64),
+        Some("unbounded") => config = config.cell_size(CellSize::Unbounded),
+        _                 => (),
+    }
+
+    match matches.value_of("OVERFLOW") {
+        Some("saturate") => config = config.overflow(Overflow::Saturate),
+        Some("trap")     => config = config.overflow(Overflow::Trap),
+        _                => (),
+    }
+
+    if let Some(path) = matches.value_of("DRAWING") {
+        match File::create(path) {
+            Ok(file) => config = config.drawing_output(file),
+            Err(e) => {
+                print_error!("The file \"{}\" could not be created: {}", path, e);
+                return 1;
+            }
+        }
+    }
+
+    let sleep = matches.value_of("SLEEP").and_then(|s| s.parse::<u64>().ok());
+
+    let exit = if matches.is_present("UNEFUNGE") {
+        execute(&code, Unefunge, config, sleep, &mut timing)
+    } else if matches.is_present("TREFUNGE") {
+        execute(&code, Trefunge, config, sleep, &mut timing)
+    } else {
+        execute(&code, Befunge, config, sleep, &mut timing)
+    };
+
+    if let Some((t0, t2)) = timing {
+        let exec = t2.elapsed();
+        let _ = io::stdout().flush();
+        let total = t0.elapsed();
+
+        print_info!("executed in {:?}", exec);
+        print_info!("total time {:?}", total);
+    }
+
+    exit
+}
+
+fn execute<D: Dimension>(
+    code: &str,
+    dimension: D,
+    config: Config,
+    sleep: Option<u64>,
+    timing: &mut Option<(Instant, Instant)>,
+) -> i32 {
+    let mut prog = Program::read_in(code, dimension).config(config);
 
-    if let Some((t0, t1)) = timing {
+    if let Some((t0, t1)) = *timing {
         let t2 = Instant::now();
         let elapsed = t2.duration_since(t1);
 
         print_info!("loaded program in {:?}", elapsed);
 
-        timing = Some((t0, t2));
+        *timing = Some((t0, t2));
     }
 
-    let exit = {
-        if let Some(n) = matches.value_of("SLEEP").and_then(|s| s.parse::<u64>().ok()) {
-            let dur = Duration::from_millis(n);
+    let exit = if let Some(n) = sleep {
+        let dur = Duration::from_millis(n);
 
-            loop {
-                prog.step_all();
+        loop {
+            prog.step_all();
 
-                if let Some(exit) = prog.exit_status() {
-                    break exit;
-                }
-
-                thread::sleep(dur);
+            if let Some(exit) = prog.exit_status() {
+                break exit;
             }
-        } else {
-            prog.run()
+
+            thread::sleep(dur);
         }
+    } else {
+        prog.run()
     };
 
-    if let Some((t0, t2)) = timing {
-        let exec = t2.elapsed();
+    if let Some(e) = prog.error() {
         let _ = io::stdout().flush();
-        let total = t0.elapsed();
-
-        print_info!("executed in {:?}", exec);
-        print_info!("total time {:?}", total);
+        print_error!("{}", e);
     }
 
     exit
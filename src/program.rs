@@ -19,35 +19,63 @@
 
 mod ip;
 
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
 use crate::config::Config;
-use crate::data::Value;
+use crate::data::{Befunge, Dimension, Value, Point, Delta};
 use crate::data::space::Space;
 use self::ip::Ip;
 
-/// An instance of a Befunge-98 program.
+use self::ip::fingerprint::Turtle;
+
+pub use self::ip::fingerprint::{Fingerprint, Handle};
+
+/// An instance of a Funge-98 program.
 ///
 /// This manages all data associated to the running program, like the
 /// addressable space, all currently active instruction pointers and program
 /// configuration.
-pub struct Program<'env> {
-    context: Context<'env>,
-    ip_data: IpData,
+///
+/// The [`Dimension`] `D` selects whether the program is a Unefunge, Befunge or
+/// Trefunge program.
+///
+/// [`Dimension`]: trait.Dimension.html
+pub struct Program<'env, D: Dimension = Befunge> {
+    context: Context<'env, D>,
+    ip_data: IpData<D>,
 }
 
 impl<'env> Program<'env> {
-    fn init(space: Space, config: Config<'env>) -> Self {
+    /// Creates a new empty Befunge `Program`.
+    pub fn new() -> Self {
+        Program::new_in(Befunge)
+    }
+
+    /// Initializes a Befunge `Program` with the given source code.
+    pub fn read(code: &str) -> Self {
+        Program::read_in(code, Befunge)
+    }
+}
+
+impl<'env, D: Dimension> Program<'env, D> {
+    fn init(space: Space<D>) -> Self {
         let ip = Ip::new();
 
         let context = Context {
             space,
-            config,
+            config: Config::new(),
             control: Control(Vec::new()),
+            references: References::default(),
+            turtle: Turtle::default(),
         };
 
         let ip_data = IpData {
             ips: vec![ip],
             current: 0,
             exit: None,
+            error: None,
             new_id: 1,
         };
 
@@ -57,20 +85,30 @@ impl<'env> Program<'env> {
         }
     }
 
-    /// Creates a new empty `Program`.
-    pub fn new() -> Self {
-        Program::init(Space::new(), Config::new())
+    /// Creates a new empty `Program` with the given [`Dimension`].
+    ///
+    /// [`Dimension`]: trait.Dimension.html
+    pub fn new_in(_: D) -> Self {
+        Program::init(Space::new())
     }
 
-    /// Initializes a `Program` with the given source code.
-    pub fn read(code: &str) -> Self {
-        Program::init(Space::read(code), Config::new())
+    /// Initializes a `Program` with the given [`Dimension`] and source code.
+    ///
+    /// Only Trefunge source code is split into planes at form feeds.
+    ///
+    /// [`Dimension`]: trait.Dimension.html
+    pub fn read_in(code: &str, _: D) -> Self {
+        Program::init(Space::read(code))
     }
 
     /// Sets the `Program`'s [`Config`].
     ///
     /// [`Config`]: struct.Config.html
     pub fn config(mut self, config: Config<'env>) -> Self {
+        for ip in &mut self.ip_data.ips {
+            ip.set_cell_size(config.get_cell_size());
+        }
+
         self.context.config = config;
         self
     }
@@ -78,10 +116,17 @@ impl<'env> Program<'env> {
     /// Returns the exit status if the `Program` has finished.
     ///
     /// If the `Program` is not yet done, `None` is returned.
-    pub fn exit_status(&self) -> Option<Value> {
+    pub fn exit_status(&self) -> Option<i32> {
         self.ip_data.exit
     }
 
+    /// Returns the error that stopped the `Program`, if any.
+    ///
+    /// A `Program` that stopped with an error has the exit status `1`.
+    pub fn error(&self) -> Option<&OverflowError> {
+        self.ip_data.error.as_ref()
+    }
+
     /// Executes the current instruction of a single instruction pointer.
     ///
     /// The IP will execute a single 'tick' as defined by the Funge-98
@@ -116,8 +161,14 @@ impl<'env> Program<'env> {
     ///
     /// Instructions will continuously be executed until the program encounters
     /// an error, all instruction pointers stop by encountering an
-    /// `@` instruction or the program is stopped with a `q` instruction.
-    pub fn run(&mut self) -> Value {
+    /// `@` instruction or the program is stopped with a `q` instruction. An
+    /// error can be retrieved with [`error`].
+    ///
+    /// Returns the exit status. A status passed to `q` that does not fit into
+    /// an `i32` wraps around.
+    ///
+    /// [`error`]: #method.error
+    pub fn run(&mut self) -> i32 {
         loop {
             self.step_all();
 
@@ -128,17 +179,68 @@ impl<'env> Program<'env> {
     }
 }
 
+impl<'env, D: Dimension + Default> Default for Program<'env, D> {
+    fn default() -> Self {
+        Program::new_in(D::default())
+    }
+}
+
+/// The error that stops a [`Program`] when an arithmetic instruction overflows
+/// and [`Overflow::Trap`] is set.
+///
+/// [`Program`]: struct.Program.html
+/// [`Overflow::Trap`]: enum.Overflow.html#variant.Trap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    id: Value,
+    command: char,
+    position: Point,
+}
+
+impl OverflowError {
+    pub(crate) fn new(id: Value, command: char, position: Point) -> Self {
+        Self {
+            id,
+            command,
+            position,
+        }
+    }
+
+    /// Returns the ID of the IP that caused the overflow.
+    pub fn id(&self) -> Value {
+        self.id
+    }
+
+    /// Returns the command that overflowed.
+    pub fn command(&self) -> char {
+        self.command
+    }
+
+    /// Returns the position of the command.
+    pub fn position(&self) -> Point {
+        self.position
+    }
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IP {} overflowed executing {} at {}", self.id, self.command, self.position)
+    }
+}
+
+impl Error for OverflowError {}
+
 /// A structure to track changes done to the control state of a [`Program`] by
 /// an [`Ip`].
 ///
 /// Methods are provided to add a new [`Ip`], delete the current [`Ip`] and to
-/// terminate the [`Program`].
+/// terminate or trap the [`Program`].
 ///
 /// [`Ip`]: ip/struct.Ip.html
 /// [`Program`]: struct.Program.html
-struct Control(Vec<ExecResult>);
+struct Control<D: Dimension>(Vec<ExecResult<D>>);
 
-impl Control {
+impl<D: Dimension> Control<D> {
     /// Adds an [`Ip`] to the list.
     ///
     /// This method only takes note that this operation should be performed, the
@@ -146,8 +248,8 @@ impl Control {
     ///
     /// [`Ip`]: ip/struct.Ip.html
     /// [`Program`]: struct.Program.html
-    fn add_ip(&mut self, ip: Ip) {
-        self.0.push(ExecResult::AddIp(ip));
+    fn add_ip(&mut self, ip: Ip<D>) {
+        self.0.push(ExecResult::AddIp(Box::new(ip)));
     }
 
     /// Deletes the current [`Ip`] from the list.
@@ -171,24 +273,73 @@ impl Control {
     fn terminate(&mut self, v: Value) {
         self.0.push(ExecResult::Terminate(v));
     }
+
+    /// Stops the program with the given [`OverflowError`].
+    ///
+    /// This method only takes note that this operation should be performed, the
+    /// [`Program`] is responsible for actually commiting this change.
+    ///
+    /// [`OverflowError`]: struct.OverflowError.html
+    /// [`Program`]: struct.Program.html
+    fn trap(&mut self, error: OverflowError) {
+        self.0.push(ExecResult::Trap(error));
+    }
+}
+
+/// A table of vectors referenced by scalar IDs, as used by the `REFC`
+/// fingerprint.
+///
+/// The table is shared by all [`Ip`]s of a [`Program`].
+///
+/// [`Ip`]: ip/struct.Ip.html
+/// [`Program`]: struct.Program.html
+#[derive(Default)]
+struct References {
+    vectors: Vec<Delta>,
+    ids: HashMap<Delta, Value>,
+}
+
+impl References {
+    /// Returns the ID referencing the given vector.
+    ///
+    /// If the vector has not been referenced before, it is added to the table.
+    fn reference(&mut self, vector: Delta) -> Value {
+        let vectors = &mut self.vectors;
+
+        *self.ids.entry(vector).or_insert_with(|| {
+            vectors.push(vector);
+            vectors.len() as Value
+        })
+    }
+
+    /// Returns the vector referenced by the given ID, if there is one.
+    fn dereference(&self, id: Value) -> Option<Delta> {
+        if id <= 0 {
+            None
+        } else {
+            self.vectors.get(id as usize - 1).cloned()
+        }
+    }
 }
 
 /// The state of the [`Program`] that can be manipulated by the [`Ip`].
 ///
 /// [`Program`]: struct.Program.html
 /// [`Ip`]: ip/struct.Ip.html
-pub(crate) struct Context<'env> {
-    control: Control,
-    space: Space,
+pub(crate) struct Context<'env, D: Dimension> {
+    control: Control<D>,
+    space: Space<D>,
     config: Config<'env>,
+    references: References,
+    turtle: Turtle,
 }
 
-impl<'env> Context<'env> {
+impl<'env, D: Dimension> Context<'env, D> {
     /// Commits all changes registered on this `Context`.
     ///
     /// This method needs to be called exactly once after an instruction has
     /// been executed.
-    fn commit_changes(&mut self, ip_data: &mut IpData) {
+    fn commit_changes(&mut self, ip_data: &mut IpData<D>) {
         let mut offset = 1;
 
         for result in self.control.0.drain(..) {
@@ -196,7 +347,7 @@ impl<'env> Context<'env> {
                 ExecResult::AddIp(mut new) => {
                     new.set_id(ip_data.new_id);
                     ip_data.new_id += 1;
-                    ip_data.ips.insert(ip_data.current, new);
+                    ip_data.ips.insert(ip_data.current, *new);
                     offset += 1;
                 },
                 ExecResult::DeleteIp => {
@@ -204,7 +355,11 @@ impl<'env> Context<'env> {
                     offset -= 1;
                 },
                 ExecResult::Terminate(v) => {
-                    ip_data.exit = Some(v);
+                    ip_data.exit = Some(v as i32);
+                },
+                ExecResult::Trap(error) => {
+                    ip_data.exit = Some(1);
+                    ip_data.error = Some(error);
                 },
             }
         }
@@ -223,15 +378,17 @@ impl<'env> Context<'env> {
 ///
 /// [`Program`]: struct.Program.html
 /// [`Ip`]: ip/struct.Ip.html
-struct IpData {
-    ips: Vec<Ip>,
+struct IpData<D: Dimension> {
+    ips: Vec<Ip<D>>,
     current: usize,
-    exit: Option<Value>,
+    exit: Option<i32>,
+    error: Option<OverflowError>,
     new_id: Value,
 }
 
-enum ExecResult {
-    AddIp(Ip),
+enum ExecResult<D: Dimension> {
+    AddIp(Box<Ip<D>>),
     DeleteIp,
     Terminate(Value),
+    Trap(OverflowError),
 }
@@ -17,27 +17,47 @@
 
 //! A single instruction pointer in a running program.
 
+pub(super) mod fingerprint;
 mod instruction;
 
+use std::marker::PhantomData;
+use std::time::Instant;
+
 use crate::config::Trace;
-use crate::data::{Value, Point, Delta};
+use crate::data::{Dimension, Value, Point, Delta, to_char};
+use crate::data::CellSize;
+use crate::data::cell::Cell;
 use crate::data::space::Space;
 use crate::data::stack::StackStack;
 use super::Context;
+use self::fingerprint::Semantics;
 
 /// An instruction pointer in a running program.
+///
+/// The [`Dimension`] `D` of the space decides the size of the vectors the `Ip`
+/// pushes and pops and the directions it can move in.
+///
+/// [`Dimension`]: ../../data/trait.Dimension.html
 #[derive(Clone)]
-pub(super) struct Ip {
+pub(super) struct Ip<D: Dimension> {
     id: Value,
+    cell_size: CellSize,
     position: Point,
     delta: Delta,
     storage: Point,
     stacks: StackStack,
+    semantics: Semantics<D>,
+    mark: Option<Instant>,
     string: bool,
     saw_space: bool,
+    hover: bool,
+    switch: bool,
+    relative_calls: bool,
+    utc_time: bool,
+    dimension: PhantomData<D>,
 }
 
-impl Ip {
+impl<D: Dimension> Ip<D> {
     /// Creates a new `Ip` at the origin, facing east.
     ///
     /// The `Ip` will be in the configuration it should have at program start:
@@ -46,22 +66,30 @@ impl Ip {
     ///
     /// [`Delta`]: ../../data/struct.Delta.html
     /// [`StackStack`]: ../../data/stack/struct.StackStack.html
-    pub(super) fn new() -> Ip {
+    pub(super) fn new() -> Self {
         Ip {
             id: 0,
-            position: Point { x: -1, y: 0 },
-            delta: Delta { dx: 1, dy: 0 },
-            storage: Point { x: 0, y: 0 },
+            cell_size: CellSize::Bits32,
+            position: Point { x: -1, y: 0, z: 0 },
+            delta: Delta { dx: 1, dy: 0, dz: 0 },
+            storage: Point { x: 0, y: 0, z: 0 },
             stacks: StackStack::new(),
+            semantics: Semantics::default(),
+            mark: None,
             string: false,
             saw_space: false,
+            hover: false,
+            switch: false,
+            relative_calls: false,
+            utc_time: false,
+            dimension: PhantomData,
         }
     }
 
     /// Returns the [`Value`] at the `Ip`'s current position.
     ///
     /// [`Value`]: ../../data/struct.Value.html
-    fn get_current(&self, space: &Space) -> Value {
+    fn get_current(&self, space: &Space<D>) -> Value {
         space.get(self.position)
     }
 
@@ -70,8 +98,23 @@ impl Ip {
         self.id = id;
     }
 
+    /// Sets the size of the cells the `Ip` computes with.
+    pub(super) fn set_cell_size(&mut self, cell_size: CellSize) {
+        self.cell_size = cell_size;
+    }
+
+    /// Returns `true` if the `Ip` is confined to a single line.
+    fn is_unefunge(&self) -> bool {
+        D::COUNT == 1
+    }
+
+    /// Returns `true` if the `Ip` can move between planes.
+    fn is_trefunge(&self) -> bool {
+        D::COUNT == 3
+    }
+
     /// Executes a single command and moves the `Ip` to the next.
-    pub(super) fn tick(&mut self, ctx: &mut Context) {
+    pub(super) fn tick(&mut self, ctx: &mut Context<D>) {
         self.step(&ctx.space);
 
         if !self.string {
@@ -94,8 +137,10 @@ impl Ip {
             return;
         }
 
-        if let Some(c) = std::char::from_u32(v as u32) {
-            self.execute(ctx, c);
+        if let Some(c) = to_char(v) {
+            let position = self.position;
+
+            self.execute(ctx, c, position);
         } else {
             self.reflect();
         }
@@ -104,31 +149,32 @@ impl Ip {
     /// Advances the `Ip`'s position by one step of its current [`Delta`].
     ///
     /// [`Delta`]: ../../data/struct.Delta.html
-    fn step(&mut self, space: &Space) {
+    fn step(&mut self, space: &Space<D>) {
         self.position = space.new_position(self.position, self.delta);
     }
 
     /// Executes a single command, without moving the `Ip`'s afterwards.
-    fn execute(&mut self, ctx: &mut Context, command: char) {
-        let position = self.position;
-
+    ///
+    /// The `position` is that of the cell holding the command, which differs
+    /// from the `Ip`'s own position when the command is the operand of `k`.
+    fn execute(&mut self, ctx: &mut Context<D>, command: char, position: Point) {
         match command {
             ' '         => panic!("attempted to execute ' '"),
             '!'         => self.negate(),
             '"'         => self.string_mode(),
             '#'         => self.trampoline(ctx),
             '$'         => self.discard(),
-            '%'         => self.rem(),
+            '%'         => self.rem(ctx),
             '&'         => self.input_decimal(ctx),
             '\''        => self.fetch_char(ctx),
-            '('         => self.load_semantics(),
-            ')'         => self.unload_semantics(),
-            '*'         => self.mul(),
-            '+'         => self.add(),
+            '('         => self.load_semantics(ctx),
+            ')'         => self.unload_semantics(ctx),
+            '*'         => self.mul(ctx),
+            '+'         => self.add(ctx),
             ','         => self.output_char(ctx),
-            '-'         => self.sub(),
+            '-'         => self.sub(ctx),
             '.'         => self.output_decimal(ctx),
-            '/'         => self.div(),
+            '/'         => self.div(ctx),
             '0'         => self.push_zero(),
             '1'         => self.push_one(),
             '2'         => self.push_two(),
@@ -146,7 +192,7 @@ impl Ip {
             '>'         => self.go_east(),
             '?'         => self.randomize_delta(),
             '@'         => self.stop(ctx),
-            'A' ... 'Z' => self.reflect(), // TODO implement
+            'A' ..= 'Z' => self.fingerprint_instruction(ctx, command),
             '['         => self.turn_left(),
             '\\'        => self.swap(),
             ']'         => self.turn_right(),
@@ -160,12 +206,13 @@ impl Ip {
             'e'         => self.push_fourteen(),
             'f'         => self.push_fifteen(),
             'g'         => self.get(ctx),
-            'h'         => self.reflect(),
+            'h'         => self.go_high(),
             'i'         => self.read_file(ctx),
             'j'         => self.jump(ctx),
             'k'         => self.iterate(ctx),
-            'l'         => self.reflect(),
-            'm'         => self.reflect(),
+            'l'         => self.go_low(),
+            'm' if !self.is_trefunge() => self.reflect(),
+            'm'         => self.if_high_low(),
             'n'         => self.clear(),
             'o'         => self.write_file(ctx),
             'p'         => self.put(ctx),
@@ -175,20 +222,41 @@ impl Ip {
             't'         => self.split(ctx),
             'u'         => self.dig(),
             'v'         => self.go_south(),
+            'w' if self.is_unefunge() => self.reflect(),
             'w'         => self.compare(),
             'x'         => self.absolute_delta(),
             'y'         => self.get_sysinfo(ctx),
             'z'         => (),
             '{'         => self.begin_block(),
+            '|' if self.is_unefunge() => self.reflect(),
             '|'         => self.if_north_south(),
             '}'         => self.end_block(),
             '~'         => self.input_char(ctx),
             _           => self.reflect(),
         }
 
+        if self.switch {
+            self.switch_command(ctx, position, command);
+        }
+
         ctx.config.do_trace(Trace::new(self.id, command, position, &self.stacks));
     }
 
+    /// Replaces a bracket that was executed in switch mode by its counterpart.
+    fn switch_command(&self, ctx: &mut Context<D>, position: Point, command: char) {
+        let c = match command {
+            '(' => ')',
+            ')' => '(',
+            '[' => ']',
+            ']' => '[',
+            '{' => '}',
+            '}' => '{',
+            _   => return,
+        };
+
+        ctx.space.set(position, c as Value);
+    }
+
     /// Sets the `Ip`'s [`Delta`] to a new value.
     ///
     /// [`Delta`]: ../../data/struct.Delta.html
@@ -198,10 +266,20 @@ impl Ip {
 
     /// Pushes a [`Value`] to the `Ip`'s [`StackStack`].
     ///
+    /// The [`Value`] wraps around if it does not fit into a cell.
+    ///
     /// [`Value`]: ../../data/struct.Value.html
     /// [`StackStack`]: ../../data/stack/struct.StackStack.html
     fn push(&mut self, value: Value) {
-        self.stacks.push(value);
+        self.stacks.push(self.cell_size.wrap(value));
+    }
+
+    /// Pushes a [`Cell`] to the `Ip`'s [`StackStack`].
+    ///
+    /// [`Cell`]: ../../data/cell/enum.Cell.html
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    fn push_cell(&mut self, cell: Cell) {
+        self.stacks.push_cell(cell);
     }
 
     /// Pushes a string on the `Ip`'s [`StackStack`].
@@ -221,6 +299,14 @@ impl Ip {
         self.stacks.pop()
     }
 
+    /// Pops the top [`Cell`] off the `Ip`'s [`StackStack`].
+    ///
+    /// [`Cell`]: ../../data/cell/enum.Cell.html
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    fn pop_cell(&mut self) -> Cell {
+        self.stacks.pop_cell()
+    }
+
     /// Pops a string off the `Ip`'s [`StackStack`].
     ///
     /// [`StackStack`]: ../../data/stack/struct.StackStack.html
@@ -228,11 +314,59 @@ impl Ip {
         self.stacks.pop_string()
     }
 
+    /// Pops a vector off the `Ip`'s [`StackStack`].
+    ///
+    /// The vector has one component for each dimension of the space, the last
+    /// of which is popped first. Missing components are `0`.
+    ///
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    fn pop_delta(&mut self) -> Delta {
+        self.stacks.pop_vector::<D>()
+    }
+
+    /// Pops the size of a block of Funge-space off the `Ip`'s [`StackStack`].
+    ///
+    /// This is a vector like that of [`pop_delta`], except that the extent
+    /// along the dimensions the space does not have is `1`.
+    ///
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    /// [`pop_delta`]: #method.pop_delta
+    fn pop_size(&mut self) -> Delta {
+        let size = self.pop_delta();
+
+        match D::COUNT {
+            1 => Delta { dy: 1, dz: 1, ..size },
+            2 => Delta { dz: 1, ..size },
+            _ => size,
+        }
+    }
+
+    /// Pushes a vector to the `Ip`'s [`StackStack`].
+    ///
+    /// One component is pushed for each dimension of the space, the last one
+    /// on top.
+    ///
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    fn push_delta(&mut self, delta: Delta) {
+        self.stacks.push_vector::<D>(delta);
+    }
+
+    /// Pops a vector off the `Ip`'s [`StackStack`] and returns the [`Point`]
+    /// it designates relative to the storage offset.
+    ///
+    /// [`StackStack`]: ../../data/stack/struct.StackStack.html
+    /// [`Point`]: ../../data/struct.Point.html
+    fn pop_position(&mut self) -> Point {
+        let delta = self.pop_delta();
+
+        self.storage + delta
+    }
+
     /// Advances the `Ip`'s position to the next command in its path.
     ///
     /// Any intervening empty space or areas delimited by semicolons will be
     /// skipped.
-    pub(super) fn find_command(&mut self, space: &Space) {
+    pub(super) fn find_command(&mut self, space: &Space<D>) {
         let mut skip = false;
 
         loop {
@@ -248,13 +382,15 @@ impl Ip {
     }
 
     /// Finds the next command in the `Ip`'s path, without moving it.
-    fn peek_command(&mut self, space: &Space) -> Value {
+    ///
+    /// Returns the position of the command along with the command itself.
+    fn peek_command(&mut self, space: &Space<D>) -> (Point, Value) {
         let orig_position = self.position;
 
         self.step(space);
         self.find_command(space);
 
-        let ret = self.get_current(space);
+        let ret = (self.position, self.get_current(space));
 
         self.position = orig_position;
 
@@ -272,7 +408,7 @@ impl Ip {
     ///
     /// [`find_command`]: #method.find_command
     /// [`StackStack`]: ../../data/stack/struct.StackStack.html
-    fn skip_space(&mut self, space: &Space) {
+    fn skip_space(&mut self, space: &Space<D>) {
         while self.get_current(space) == 32 {
             self.step(space);
         }
@@ -0,0 +1,456 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Fingerprints extending the instruction set of an [`Ip`].
+//!
+//! [`Ip`]: ../struct.Ip.html
+
+mod boolean;
+mod date;
+mod dirf;
+mod evar;
+mod file;
+mod fixp;
+mod frth;
+mod float;
+mod hrti;
+mod indv;
+mod jstr;
+mod mode;
+mod modu;
+mod ncrs;
+mod null;
+mod orth;
+mod refc;
+mod roma;
+mod scke;
+mod sock;
+mod strn;
+mod subr;
+mod term;
+mod three_dsp;
+mod time;
+mod toys;
+mod turt;
+
+use crate::data::{Dimension, Value, Point, Delta};
+use crate::data::space::Space;
+use crate::program::{Context, Control};
+use super::Ip;
+
+pub(crate) use self::turt::Turtle;
+
+/// The number of instructions that can be bound by fingerprints, `A` to `Z`.
+const NUM_INSTRUCTIONS: usize = 26;
+
+/// A set of instructions that a program can load with the `(` instruction.
+///
+/// Fingerprints are registered with a [`Config`] under the ID a program uses to
+/// load them. Once loaded, each of the instructions the fingerprint defines is
+/// executed by calling [`execute`] with a [`Handle`] to the executing IP.
+///
+/// [`Config`]: struct.Config.html
+/// [`execute`]: #tymethod.execute
+/// [`Handle`]: struct.Handle.html
+pub trait Fingerprint {
+    /// Returns the instructions defined by this fingerprint.
+    ///
+    /// Any characters outside the range `A` to `Z` are ignored.
+    fn instructions(&self) -> &str;
+
+    /// Executes one of the instructions defined by this fingerprint.
+    ///
+    /// Returns `true` if the instruction succeeded. Otherwise, the executing IP
+    /// will be reflected.
+    fn execute(&mut self, instruction: char, handle: &mut Handle) -> bool;
+}
+
+/// A handle to the IP executing an instruction of a [`Fingerprint`].
+///
+/// This gives access to the IP's stacks and movement, to the space of the
+/// program and to the creation and removal of IPs.
+///
+/// [`Fingerprint`]: trait.Fingerprint.html
+pub struct Handle<'a> {
+    target: &'a mut dyn Target,
+}
+
+impl<'a> Handle<'a> {
+    /// Returns the ID of the IP.
+    pub fn id(&self) -> Value {
+        self.target.id()
+    }
+
+    /// Pushes a value to the IP's stack.
+    pub fn push(&mut self, value: Value) {
+        self.target.push(value);
+    }
+
+    /// Pops a value off the IP's stack.
+    ///
+    /// If the stack is empty, `0` will be returned.
+    pub fn pop(&mut self) -> Value {
+        self.target.pop()
+    }
+
+    /// Pushes a 0-terminated string to the IP's stack.
+    ///
+    /// Returns the number of cells that were pushed.
+    pub fn push_string(&mut self, s: &str) -> usize {
+        self.target.push_string(s)
+    }
+
+    /// Pops a 0-terminated string off the IP's stack.
+    ///
+    /// Returns `None` if the string does not consist of valid characters.
+    pub fn pop_string(&mut self) -> Option<String> {
+        self.target.pop_string()
+    }
+
+    /// Returns the position of the IP.
+    pub fn position(&self) -> Point {
+        self.target.position()
+    }
+
+    /// Moves the IP to the given position.
+    ///
+    /// The IP will advance from there before executing its next instruction.
+    pub fn set_position(&mut self, position: Point) {
+        self.target.set_position(position);
+    }
+
+    /// Returns the delta of the IP.
+    pub fn delta(&self) -> Delta {
+        self.target.delta()
+    }
+
+    /// Sets the delta of the IP.
+    pub fn set_delta(&mut self, delta: Delta) {
+        self.target.set_delta(delta);
+    }
+
+    /// Returns the storage offset of the IP.
+    pub fn storage_offset(&self) -> Point {
+        self.target.storage_offset()
+    }
+
+    /// Sets the storage offset of the IP.
+    pub fn set_storage_offset(&mut self, storage: Point) {
+        self.target.set_storage_offset(storage);
+    }
+
+    /// Retrieves the value at the given position in the program's space.
+    ///
+    /// The storage offset is not taken into account.
+    pub fn get(&self, position: Point) -> Value {
+        self.target.get(position)
+    }
+
+    /// Puts the value at the given position in the program's space.
+    ///
+    /// The storage offset is not taken into account.
+    pub fn set(&mut self, position: Point, value: Value) {
+        self.target.set(position, value);
+    }
+
+    /// Creates a copy of the IP that will move with the given delta.
+    ///
+    /// The new IP will first be executed in the next tick, right before the
+    /// current one. The `t` instruction is equivalent to spawning an IP with
+    /// the reverse of the current delta.
+    pub fn spawn(&mut self, delta: Delta) {
+        self.target.spawn(delta);
+    }
+
+    /// Stops the IP after the current instruction, as with the `@`
+    /// instruction.
+    pub fn stop(&mut self) {
+        self.target.stop();
+    }
+
+    /// Terminates the program with the given exit status, as with the `q`
+    /// instruction.
+    pub fn terminate(&mut self, status: Value) {
+        self.target.terminate(status);
+    }
+}
+
+/// The operations a [`Handle`] performs, for a program of any [`Dimension`].
+///
+/// [`Handle`]: struct.Handle.html
+/// [`Dimension`]: ../../../data/trait.Dimension.html
+trait Target {
+    fn id(&self) -> Value;
+    fn push(&mut self, value: Value);
+    fn pop(&mut self) -> Value;
+    fn push_string(&mut self, s: &str) -> usize;
+    fn pop_string(&mut self) -> Option<String>;
+    fn position(&self) -> Point;
+    fn set_position(&mut self, position: Point);
+    fn delta(&self) -> Delta;
+    fn set_delta(&mut self, delta: Delta);
+    fn storage_offset(&self) -> Point;
+    fn set_storage_offset(&mut self, storage: Point);
+    fn get(&self, position: Point) -> Value;
+    fn set(&mut self, position: Point, value: Value);
+    fn spawn(&mut self, delta: Delta);
+    fn stop(&mut self);
+    fn terminate(&mut self, status: Value);
+}
+
+/// The parts of a running program a [`Handle`] gives access to.
+///
+/// [`Handle`]: struct.Handle.html
+struct Parts<'a, D: Dimension> {
+    ip: &'a mut Ip<D>,
+    space: &'a mut Space<D>,
+    control: &'a mut Control<D>,
+}
+
+impl<'a, D: Dimension> Target for Parts<'a, D> {
+    fn id(&self) -> Value {
+        self.ip.id
+    }
+
+    fn push(&mut self, value: Value) {
+        self.ip.push(value);
+    }
+
+    fn pop(&mut self) -> Value {
+        self.ip.pop()
+    }
+
+    fn push_string(&mut self, s: &str) -> usize {
+        self.ip.push_string(s)
+    }
+
+    fn pop_string(&mut self) -> Option<String> {
+        self.ip.pop_string()
+    }
+
+    fn position(&self) -> Point {
+        self.ip.position
+    }
+
+    fn set_position(&mut self, position: Point) {
+        self.ip.position = position;
+    }
+
+    fn delta(&self) -> Delta {
+        self.ip.delta
+    }
+
+    fn set_delta(&mut self, delta: Delta) {
+        self.ip.set_delta(delta);
+    }
+
+    fn storage_offset(&self) -> Point {
+        self.ip.storage
+    }
+
+    fn set_storage_offset(&mut self, storage: Point) {
+        self.ip.storage = storage;
+    }
+
+    fn get(&self, position: Point) -> Value {
+        self.space.get(position)
+    }
+
+    fn set(&mut self, position: Point, value: Value) {
+        self.space.set(position, value);
+    }
+
+    fn spawn(&mut self, delta: Delta) {
+        let mut ip = self.ip.clone();
+
+        ip.set_delta(delta);
+        self.control.add_ip(ip);
+    }
+
+    fn stop(&mut self) {
+        self.control.delete_ip();
+    }
+
+    fn terminate(&mut self, status: Value) {
+        self.control.terminate(status);
+    }
+}
+
+/// The implementation of a built-in fingerprint's instruction.
+type Function<D> = fn(&mut Ip<D>, &mut Context<D>);
+
+/// The implementation of an instruction provided by a fingerprint.
+#[derive(Clone, Copy)]
+pub(super) enum Instruction<D: Dimension> {
+    /// An instruction implemented by the interpreter.
+    Builtin(Function<D>),
+    /// An instruction of the [`Fingerprint`] registered under the given ID.
+    ///
+    /// [`Fingerprint`]: trait.Fingerprint.html
+    Custom(Value),
+}
+
+/// A fingerprint implemented by the interpreter itself.
+struct Builtin<D: Dimension> {
+    /// The ID of the fingerprint, as computed by the `(` instruction.
+    id: Value,
+    /// The instructions defined by the fingerprint.
+    instructions: &'static [(char, Function<D>)],
+}
+
+/// Returns all fingerprints supported by the interpreter.
+fn builtins<D: Dimension>() -> Vec<Builtin<D>> {
+    vec![
+        boolean::bool(),
+        date::date(),
+        dirf::dirf(),
+        evar::evar(),
+        file::file(),
+        fixp::fixp(),
+        frth::frth(),
+        float::fpdp(),
+        float::fpsp(),
+        hrti::hrti(),
+        indv::indv(),
+        jstr::jstr(),
+        mode::mode(),
+        modu::modu(),
+        ncrs::ncrs(),
+        null::null(),
+        orth::orth(),
+        refc::refc(),
+        roma::roma(),
+        scke::scke(),
+        sock::sock(),
+        strn::strn(),
+        subr::subr(),
+        term::term(),
+        three_dsp::three_dsp(),
+        time::time(),
+        toys::toys(),
+        turt::turt(),
+    ]
+}
+
+/// Looks up the instructions of the fingerprint with the given ID.
+///
+/// Fingerprints registered with the [`Config`] take precedence over the
+/// interpreter's built-in ones. Returns `None` if no fingerprint with this ID
+/// is available.
+///
+/// [`Config`]: ../../../config/struct.Config.html
+pub(super) fn find<D: Dimension>(
+    ctx: &Context<D>,
+    id: Value,
+) -> Option<Vec<(char, Instruction<D>)>> {
+    if let Some(fp) = ctx.config.find_fingerprint(id) {
+        let instructions = fp.instructions()
+            .chars()
+            .filter(char::is_ascii_uppercase)
+            .map(|c| (c, Instruction::Custom(id)))
+            .collect();
+
+        return Some(instructions);
+    }
+
+    builtins().iter().find(|fp| fp.id == id).map(|fp| {
+        fp.instructions
+            .iter()
+            .map(|&(c, f)| (c, Instruction::Builtin(f)))
+            .collect()
+    })
+}
+
+/// The stacks of semantics bound to the instructions `A` to `Z` of an [`Ip`].
+///
+/// Loading a fingerprint pushes its implementation of each instruction it
+/// defines onto the corresponding stack, unloading it pops these stacks again.
+/// Only the topmost semantics of each stack is ever executed.
+///
+/// [`Ip`]: ../struct.Ip.html
+#[derive(Clone)]
+pub(super) struct Semantics<D: Dimension> {
+    stacks: [Vec<Instruction<D>>; NUM_INSTRUCTIONS],
+}
+
+impl<D: Dimension> Semantics<D> {
+    /// Pushes the given instructions onto their stacks.
+    pub(super) fn load(&mut self, instructions: &[(char, Instruction<D>)]) {
+        for &(c, f) in instructions {
+            self.stacks[index(c)].push(f);
+        }
+    }
+
+    /// Pops the stacks of all the given instructions.
+    ///
+    /// The semantics that are removed need not be the ones that were provided
+    /// by the fingerprint being unloaded.
+    pub(super) fn unload(&mut self, instructions: &[(char, Instruction<D>)]) {
+        for &(c, _) in instructions {
+            self.stacks[index(c)].pop();
+        }
+    }
+
+    /// Returns the semantics currently bound to the given instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `c` is not in the range `A` to `Z`.
+    pub(super) fn get(&self, c: char) -> Option<Instruction<D>> {
+        self.stacks[index(c)].last().cloned()
+    }
+}
+
+impl<D: Dimension> Default for Semantics<D> {
+    fn default() -> Self {
+        Semantics {
+            stacks: Default::default(),
+        }
+    }
+}
+
+impl<D: Dimension> Ip<D> {
+    /// Executes an instruction of the [`Fingerprint`] registered under the
+    /// given ID.
+    ///
+    /// [`Fingerprint`]: trait.Fingerprint.html
+    pub(super) fn execute_custom(&mut self, ctx: &mut Context<D>, id: Value, c: char) {
+        let ok = match ctx.config.find_fingerprint_mut(id) {
+            Some(fp) => {
+                let mut parts = Parts {
+                    ip: self,
+                    space: &mut ctx.space,
+                    control: &mut ctx.control,
+                };
+                let mut handle = Handle {
+                    target: &mut parts,
+                };
+
+                fp.execute(c, &mut handle)
+            },
+            None     => false,
+        };
+
+        if !ok {
+            self.reflect();
+        }
+    }
+}
+
+fn index(c: char) -> usize {
+    (c as u8 - b'A') as usize
+}
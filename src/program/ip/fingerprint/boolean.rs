@@ -0,0 +1,62 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `BOOL` fingerprint, providing bitwise logic operations.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn bool<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x424f_4f4c,
+        instructions: &[
+            ('A', and),
+            ('N', not),
+            ('O', or),
+            ('X', xor),
+        ],
+    }
+}
+
+fn and<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a & b);
+}
+
+fn not<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(!v);
+}
+
+fn or<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a | b);
+}
+
+fn xor<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a ^ b);
+}
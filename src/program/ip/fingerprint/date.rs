@@ -0,0 +1,127 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `DATE` fingerprint, providing calendar arithmetic.
+//!
+//! Dates are given as year, month and day, with the day on top. Days of the
+//! week and of the year are counted from 0, starting with Monday and January 1.
+
+use std::convert::TryFrom;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+/// The Julian day number of January 1, 1 CE minus one.
+const JULIAN_OFFSET: i64 = 1_721_425;
+
+pub(super) fn date<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4441_5445,
+        instructions: &[
+            ('A', add_days),
+            ('C', from_julian),
+            ('D', difference),
+            ('J', to_julian),
+            ('T', from_day_of_year),
+            ('W', day_of_week),
+            ('Y', day_of_year),
+        ],
+    }
+}
+
+fn pop_date<D: Dimension>(ip: &mut Ip<D>) -> Option<NaiveDate> {
+    let d = ip.pop();
+    let m = ip.pop();
+    let y = ip.pop();
+
+    let y = i32::try_from(y).ok()?;
+    let m = u32::try_from(m).ok()?;
+    let d = u32::try_from(d).ok()?;
+
+    NaiveDate::from_ymd_opt(y, m, d)
+}
+
+fn push_date<D: Dimension>(ip: &mut Ip<D>, date: NaiveDate) {
+    ip.push(Value::from(date.year()));
+    ip.push(date.month() as Value);
+    ip.push(date.day() as Value);
+}
+
+fn julian_day(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) + JULIAN_OFFSET
+}
+
+fn add_days<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let days = i32::try_from(ip.pop()).ok();
+
+    match pop_date(ip).and_then(|date| date.checked_add_signed(Duration::days(i64::from(days?)))) {
+        Some(date) => push_date(ip, date),
+        None       => ip.reflect(),
+    }
+}
+
+fn from_julian<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let jd = ip.pop().saturating_sub(JULIAN_OFFSET);
+
+    match i32::try_from(jd).ok().and_then(NaiveDate::from_num_days_from_ce_opt) {
+        Some(date) => push_date(ip, date),
+        None       => ip.reflect(),
+    }
+}
+
+/// Pushes the number of days from the second date to the first.
+fn difference<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match (pop_date(ip), pop_date(ip)) {
+        (Some(b), Some(a)) => ip.push(a.signed_duration_since(b).num_days() as Value),
+        _                  => ip.reflect(),
+    }
+}
+
+fn to_julian<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match pop_date(ip) {
+        Some(date) => ip.push(julian_day(date) as Value),
+        None       => ip.reflect(),
+    }
+}
+
+fn from_day_of_year<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let day = u32::try_from(ip.pop()).ok();
+    let year = i32::try_from(ip.pop()).ok();
+
+    match year.zip(day).and_then(|(y, d)| NaiveDate::from_yo_opt(y, d.checked_add(1)?)) {
+        Some(date) => push_date(ip, date),
+        None       => ip.reflect(),
+    }
+}
+
+fn day_of_week<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match pop_date(ip) {
+        Some(date) => ip.push(date.weekday().num_days_from_monday() as Value),
+        None       => ip.reflect(),
+    }
+}
+
+fn day_of_year<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match pop_date(ip) {
+        Some(date) => ip.push(date.ordinal0() as Value),
+        None       => ip.reflect(),
+    }
+}
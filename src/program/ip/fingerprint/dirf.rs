@@ -0,0 +1,55 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `DIRF` fingerprint, providing directory functions.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn dirf<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4449_5246,
+        instructions: &[
+            ('C', change_dir),
+            ('M', make_dir),
+            ('R', remove_dir),
+        ],
+    }
+}
+
+fn change_dir<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(path) if ctx.config.change_dir(&path) => (),
+        _                                          => ip.reflect(),
+    }
+}
+
+fn make_dir<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(path) if ctx.config.make_dir(&path) => (),
+        _                                        => ip.reflect(),
+    }
+}
+
+fn remove_dir<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(path) if ctx.config.remove_dir(&path) => (),
+        _                                          => ip.reflect(),
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `EVAR` fingerprint, accessing the environment variables of the `Config`.
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn evar<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4556_4152,
+        instructions: &[
+            ('G', get),
+            ('N', count),
+            ('P', put),
+            ('V', nth),
+        ],
+    }
+}
+
+/// Pushes the value of a variable, or an empty string if it is not set.
+fn get<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(name) => {
+            let value = ctx.config.get_env(&name).unwrap_or_default();
+
+            ip.push_string(value);
+        },
+        None       => ip.reflect(),
+    }
+}
+
+fn count<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    ip.push(ctx.config.env_vars().count() as Value);
+}
+
+/// Sets a variable from a string of the form `NAME=VALUE`.
+fn put<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = match ip.pop_string() {
+        Some(s) => s,
+        None    => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    match s.find('=') {
+        Some(i) if i > 0 => ctx.config.set_env(&s[..i], &s[i + 1..]),
+        _                => ip.reflect(),
+    }
+}
+
+/// Pushes the `n`th variable as a string of the form `NAME=VALUE`.
+fn nth<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+
+    if n < 0 {
+        ip.reflect();
+        return;
+    }
+
+    match ctx.config.env_vars().nth(n as usize) {
+        Some((k, v)) => {
+            let var = format!("{}={}", k, v);
+
+            ip.push_string(&var);
+        },
+        None         => ip.reflect(),
+    }
+}
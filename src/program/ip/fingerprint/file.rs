@@ -0,0 +1,184 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `FILE` fingerprint, providing handle-based file I/O.
+
+use crate::data::{Dimension, Value, Delta};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+/// The greatest number of cells written to a file at once by `W`.
+const CHUNK: Value = 4096;
+
+/// The step from one cell of a buffer to the next.
+const EAST: Delta = Delta { dx: 1, dy: 0, dz: 0 };
+
+pub(super) fn file<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4649_4c45,
+        instructions: &[
+            ('C', close),
+            ('D', delete),
+            ('G', get_line),
+            ('L', tell),
+            ('O', open),
+            ('P', put_string),
+            ('R', read),
+            ('S', seek),
+            ('W', write),
+        ],
+    }
+}
+
+fn open<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let path = ip.pop_string();
+    let mode = ip.pop();
+    let buffer = ip.pop_position();
+
+    match path.and_then(|path| ctx.config.open_file(&path, mode, buffer)) {
+        Some(h) => ip.push(h),
+        None    => ip.reflect(),
+    }
+}
+
+fn close<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let h = ip.pop();
+
+    if !ctx.config.close_file(h) {
+        ip.reflect();
+    }
+}
+
+fn delete<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(path) => if !ctx.config.delete_file(&path) {
+            ip.reflect();
+        },
+        None       => ip.reflect(),
+    }
+}
+
+fn get_line<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let h = ip.pop();
+
+    match ctx.config.read_line_from_file(h) {
+        Some(data) => {
+            let s: String = data.iter().map(|&b| char::from(b)).collect();
+
+            ip.push(h);
+            ip.push_string(&s);
+            ip.push(data.len() as Value);
+        },
+        None       => ip.reflect(),
+    }
+}
+
+fn put_string<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop_string();
+    let h = ip.pop();
+
+    match s {
+        Some(s) if ctx.config.write_to_file(h, s.as_bytes()) => ip.push(h),
+        _                                                    => ip.reflect(),
+    }
+}
+
+fn tell<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let h = ip.pop();
+
+    match ctx.config.file_position(h) {
+        Some(pos) => {
+            ip.push(h);
+            ip.push(pos);
+        },
+        None      => ip.reflect(),
+    }
+}
+
+fn seek<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let offset = ip.pop();
+    let mode = ip.pop();
+    let h = ip.pop();
+
+    if ctx.config.seek_file(h, mode, offset) {
+        ip.push(h);
+    } else {
+        ip.reflect();
+    }
+}
+
+fn read<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let h = ip.pop();
+
+    let buffer = match ctx.config.file_buffer(h) {
+        Some(buffer) if n >= 0 => buffer,
+        _                      => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    match ctx.config.read_from_file(h, n as usize) {
+        Some(data) => {
+            let mut p = buffer;
+
+            for &b in &data {
+                ctx.space.set(p, Value::from(b));
+                p += EAST;
+            }
+
+            ip.push(h);
+        },
+        None       => ip.reflect(),
+    }
+}
+
+fn write<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let h = ip.pop();
+
+    let mut p = match ctx.config.file_buffer(h) {
+        Some(buffer) if n >= 0 => buffer,
+        _                      => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    let mut left = n;
+
+    while left > 0 {
+        let data: Vec<u8> = (0..left.min(CHUNK))
+            .map(|_| {
+                let b = ctx.space.get(p) as u8;
+                p += EAST;
+                b
+            })
+            .collect();
+
+        if !ctx.config.write_to_file(h, &data) {
+            ip.reflect();
+            return;
+        }
+
+        left -= data.len() as Value;
+    }
+
+    ip.push(h);
+}
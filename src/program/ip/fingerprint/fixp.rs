@@ -0,0 +1,173 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `FIXP` fingerprint, providing fixed point arithmetic.
+//!
+//! Fixed point numbers are scaled by 10000. Angles are given in degrees.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use crate::data::{Dimension, Value, from_float};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+/// The factor by which fixed point numbers are scaled.
+const SCALE: f64 = 10000.0;
+
+pub(super) fn fixp<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4649_5850,
+        instructions: &[
+            ('A', and),
+            ('B', acos),
+            ('C', cos),
+            ('D', random),
+            ('I', sin),
+            ('J', asin),
+            ('N', neg),
+            ('O', or),
+            ('P', mul_pi),
+            ('Q', sqrt),
+            ('R', pow),
+            ('S', sign),
+            ('T', tan),
+            ('U', atan),
+            ('V', abs),
+            ('X', xor),
+        ],
+    }
+}
+
+/// Converts a fixed point number to a floating point number.
+fn from_fixed(n: Value) -> f64 {
+    n as f64 / SCALE
+}
+
+/// Converts a floating point number to the nearest fixed point number.
+fn to_fixed(x: f64) -> Value {
+    from_float((x * SCALE).round())
+}
+
+fn and<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a & b);
+}
+
+fn or<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a | b);
+}
+
+fn xor<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a ^ b);
+}
+
+fn neg<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.wrapping_neg());
+}
+
+fn abs<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.wrapping_abs());
+}
+
+fn sign<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.signum());
+}
+
+fn sqrt<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(from_float((v as f64).sqrt()));
+}
+
+fn pow<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(from_float((a as f64).powf(b as f64)));
+}
+
+fn mul_pi<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(from_float((v as f64 * PI).round()));
+}
+
+fn random<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+    let mut rng = rand::thread_rng();
+
+    if n > 0 {
+        ip.push(rng.gen_range(0, n));
+    } else if n < 0 {
+        ip.push(rng.gen_range(n + 1, 1));
+    } else {
+        ip.push(0);
+    }
+}
+
+fn sin<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).to_radians().sin()));
+}
+
+fn cos<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).to_radians().cos()));
+}
+
+fn tan<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).to_radians().tan()));
+}
+
+fn asin<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).asin().to_degrees()));
+}
+
+fn acos<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).acos().to_degrees()));
+}
+
+fn atan<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(to_fixed(from_fixed(v).atan().to_degrees()));
+}
@@ -0,0 +1,272 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `FPSP` and `FPDP` fingerprints, providing floating point arithmetic.
+//!
+//! `FPSP` stores single precision numbers in a single cell, `FPDP` stores
+//! double precision numbers in two cells, with the high-order half below the
+//! low-order half. All computations are done in double precision.
+
+use crate::data::{Dimension, Value, from_float};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn fpsp<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4650_5350,
+        instructions: &[
+            ('A', add::<D, Single>),
+            ('B', sin::<D, Single>),
+            ('C', cos::<D, Single>),
+            ('D', div::<D, Single>),
+            ('E', asin::<D, Single>),
+            ('F', from_int::<D, Single>),
+            ('G', atan::<D, Single>),
+            ('H', acos::<D, Single>),
+            ('I', to_int::<D, Single>),
+            ('K', ln::<D, Single>),
+            ('L', log10::<D, Single>),
+            ('M', mul::<D, Single>),
+            ('N', neg::<D, Single>),
+            ('P', print::<D, Single>),
+            ('Q', sqrt::<D, Single>),
+            ('R', parse::<D, Single>),
+            ('S', sub::<D, Single>),
+            ('T', tan::<D, Single>),
+            ('V', abs::<D, Single>),
+            ('X', exp::<D, Single>),
+            ('Y', pow::<D, Single>),
+        ],
+    }
+}
+
+pub(super) fn fpdp<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4650_4450,
+        instructions: &[
+            ('A', add::<D, Double>),
+            ('B', sin::<D, Double>),
+            ('C', cos::<D, Double>),
+            ('D', div::<D, Double>),
+            ('E', asin::<D, Double>),
+            ('F', from_int::<D, Double>),
+            ('G', atan::<D, Double>),
+            ('H', acos::<D, Double>),
+            ('I', to_int::<D, Double>),
+            ('K', ln::<D, Double>),
+            ('L', log10::<D, Double>),
+            ('M', mul::<D, Double>),
+            ('N', neg::<D, Double>),
+            ('P', print::<D, Double>),
+            ('Q', sqrt::<D, Double>),
+            ('R', parse::<D, Double>),
+            ('S', sub::<D, Double>),
+            ('T', tan::<D, Double>),
+            ('V', abs::<D, Double>),
+            ('X', exp::<D, Double>),
+            ('Y', pow::<D, Double>),
+        ],
+    }
+}
+
+/// A way of storing floating point numbers on the stack.
+pub(super) trait Encoding {
+    /// Pops a floating point number off the stack.
+    fn pop<D: Dimension>(ip: &mut Ip<D>) -> f64;
+
+    /// Pushes a floating point number to the stack.
+    fn push<D: Dimension>(ip: &mut Ip<D>, x: f64);
+}
+
+/// Single precision numbers, stored in a single cell.
+pub(super) enum Single {}
+
+/// Double precision numbers, stored in two cells.
+pub(super) enum Double {}
+
+impl Single {
+    /// Decodes the number stored in a cell.
+    pub(super) fn from_cell(v: Value) -> f64 {
+        f64::from(f32::from_bits(v as u32))
+    }
+
+    /// Encodes a number in a cell.
+    pub(super) fn to_cell(x: f64) -> Value {
+        Value::from((x as f32).to_bits() as i32)
+    }
+}
+
+impl Encoding for Single {
+    fn pop<D: Dimension>(ip: &mut Ip<D>) -> f64 {
+        Single::from_cell(ip.pop())
+    }
+
+    fn push<D: Dimension>(ip: &mut Ip<D>, x: f64) {
+        ip.push(Single::to_cell(x));
+    }
+}
+
+impl Encoding for Double {
+    fn pop<D: Dimension>(ip: &mut Ip<D>) -> f64 {
+        let lo = ip.pop() as u32;
+        let hi = ip.pop() as u32;
+
+        f64::from_bits(u64::from(hi) << 32 | u64::from(lo))
+    }
+
+    fn push<D: Dimension>(ip: &mut Ip<D>, x: f64) {
+        let bits = x.to_bits();
+
+        ip.push(Value::from((bits >> 32) as i32));
+        ip.push(Value::from(bits as i32));
+    }
+}
+
+fn add<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = E::pop(ip);
+    let a = E::pop(ip);
+
+    E::push(ip, a + b);
+}
+
+fn sub<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = E::pop(ip);
+    let a = E::pop(ip);
+
+    E::push(ip, a - b);
+}
+
+fn mul<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = E::pop(ip);
+    let a = E::pop(ip);
+
+    E::push(ip, a * b);
+}
+
+fn div<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = E::pop(ip);
+    let a = E::pop(ip);
+
+    E::push(ip, a / b);
+}
+
+fn pow<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = E::pop(ip);
+    let a = E::pop(ip);
+
+    E::push(ip, a.powf(b));
+}
+
+fn neg<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, -x);
+}
+
+fn abs<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.abs());
+}
+
+fn sqrt<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.sqrt());
+}
+
+fn exp<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.exp());
+}
+
+fn ln<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.ln());
+}
+
+fn log10<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.log10());
+}
+
+fn sin<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.sin());
+}
+
+fn cos<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.cos());
+}
+
+fn tan<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.tan());
+}
+
+fn asin<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.asin());
+}
+
+fn acos<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.acos());
+}
+
+fn atan<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    E::push(ip, x.atan());
+}
+
+fn from_int<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    E::push(ip, n as f64);
+}
+
+fn to_int<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    ip.push(from_float(x));
+}
+
+fn parse<D: Dimension, E: Encoding>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.pop_string().and_then(|s| s.trim().parse().ok()) {
+        Some(x) => E::push(ip, x),
+        None    => ip.reflect(),
+    }
+}
+
+fn print<D: Dimension, E: Encoding>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let x = E::pop(ip);
+
+    if !ctx.config.write_float(x) {
+        ip.reflect();
+    }
+}
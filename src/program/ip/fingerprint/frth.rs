@@ -0,0 +1,90 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `FRTH` fingerprint, providing Forth-style stack manipulation.
+//!
+//! Cells are counted from the top of the stack, starting at 0.
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn frth<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4652_5448,
+        instructions: &[
+            ('D', depth),
+            ('L', roll),
+            ('O', over),
+            ('P', pick),
+            ('R', rotate),
+        ],
+    }
+}
+
+fn depth<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.stacks.len();
+
+    ip.push(n as Value);
+}
+
+/// Moves the `n`th cell to the top, or for negative `n`, the top cell to the
+/// `-n`th position.
+///
+/// Reflects if the top cell would be moved below the bottom of the stack.
+fn roll<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    if n >= 0 {
+        let v = ip.stacks.remove(n as usize);
+
+        ip.push_cell(v);
+    } else if n.unsigned_abs() < ip.stacks.len() as u64 {
+        let v = ip.pop_cell();
+
+        ip.stacks.insert(n.unsigned_abs() as usize, v);
+    } else {
+        ip.reflect();
+    }
+}
+
+fn over<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.stacks.peek(1);
+
+    ip.push_cell(v);
+}
+
+/// Copies the `n`th cell to the top.
+fn pick<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    if n < 0 {
+        ip.reflect();
+    } else {
+        let v = ip.stacks.peek(n as usize);
+
+        ip.push_cell(v);
+    }
+}
+
+/// Moves the third cell to the top.
+fn rotate<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.stacks.remove(2);
+
+    ip.push_cell(v);
+}
@@ -0,0 +1,64 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `HRTI` fingerprint, providing a high-resolution timer.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn hrti<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4852_5449,
+        instructions: &[
+            ('E', erase),
+            ('G', granularity),
+            ('M', mark),
+            ('S', second),
+            ('T', timer),
+        ],
+    }
+}
+
+fn granularity<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(1);
+}
+
+fn mark<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.mark = Some(Instant::now());
+}
+
+fn timer<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.mark {
+        Some(mark) => ip.push(mark.elapsed().as_micros() as Value),
+        None       => ip.reflect(),
+    }
+}
+
+fn erase<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.mark = None;
+}
+
+fn second<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(t)  => ip.push(t.subsec_micros() as Value),
+        Err(_) => ip.reflect(),
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `INDV` fingerprint, accessing Funge-space through pointers.
+//!
+//! A pointer designates a vector stored in Funge-space, with one component for
+//! each dimension of the space. The last component is in the cell the pointer
+//! points to and the others follow eastwards, so that in Befunge the y
+//! component comes first and the x component is in the cell east of it. Both
+//! pointers and the vectors they point to are relative to the storage offset.
+
+use crate::data::{Dimension, Value, Point, Delta};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn indv<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x494e_4456,
+        instructions: &[
+            ('G', get_value),
+            ('P', put_value),
+            ('V', get_vector),
+            ('W', put_vector),
+        ],
+    }
+}
+
+/// Pops a pointer and returns the position its vector designates.
+fn pop_target<D: Dimension>(ip: &mut Ip<D>, ctx: &Context<D>) -> Point {
+    let pointer = ip.pop_position();
+
+    ip.storage + read_vector(ctx, pointer)
+}
+
+/// Reads the vector stored at the given position.
+fn read_vector<D: Dimension>(ctx: &Context<D>, p: Point) -> Delta {
+    let mut v = [0; 3];
+
+    for (i, c) in v[..D::COUNT].iter_mut().rev().enumerate() {
+        *c = ctx.space.get(p + Delta { dx: i as i32, dy: 0, dz: 0 }) as i32;
+    }
+
+    Delta { dx: v[0], dy: v[1], dz: v[2] }
+}
+
+/// Stores the vector at the given position.
+fn write_vector<D: Dimension>(ctx: &mut Context<D>, p: Point, Delta { dx, dy, dz }: Delta) {
+    for (i, &c) in [dx, dy, dz][..D::COUNT].iter().rev().enumerate() {
+        ctx.space.set(p + Delta { dx: i as i32, dy: 0, dz: 0 }, Value::from(c));
+    }
+}
+
+fn get_value<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = pop_target(ip, ctx);
+
+    ip.push_cell(ctx.space.get_cell(target));
+}
+
+fn put_value<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = pop_target(ip, ctx);
+    let v = ip.pop_cell();
+
+    ctx.space.set_cell(target, v);
+}
+
+fn get_vector<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = pop_target(ip, ctx);
+
+    ip.push_delta(read_vector(ctx, target));
+}
+
+fn put_vector<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = pop_target(ip, ctx);
+    let vector = ip.pop_delta();
+
+    write_vector(ctx, target, vector);
+}
@@ -0,0 +1,89 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `JSTR` fingerprint, moving strings between the stack and Funge-space.
+//!
+//! Strings are read and written along an arbitrary delta, starting at a
+//! position relative to the storage offset.
+
+use std::convert::TryFrom;
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn jstr<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4a53_5452,
+        instructions: &[
+            ('G', get),
+            ('P', put),
+        ],
+    }
+}
+
+/// Reads `n` cells and pushes them as a 0-terminated string.
+///
+/// The cells are read backwards from the last one, so that the first ends up
+/// on top. Reflects if there is not enough memory for the string.
+fn get<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let position = ip.pop_position();
+    let delta = ip.pop_delta();
+
+    let len = match usize::try_from(n) {
+        Ok(len) if ip.stacks.reserve(len.saturating_add(1)) => len,
+        _                                                   => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    ip.push(0);
+
+    if len == 0 {
+        return;
+    }
+
+    // Coordinates wrap around, so truncating the count keeps the offset exact.
+    let mut position = position + delta * (n - 1) as i32;
+
+    for _ in 0..len {
+        ip.push_cell(ctx.space.get_cell(position));
+        position -= delta;
+    }
+}
+
+/// Pops `n` cells and writes them.
+fn put<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let mut position = ip.pop_position();
+    let delta = ip.pop_delta();
+
+    if n < 0 {
+        ip.reflect();
+        return;
+    }
+
+    for _ in 0..n {
+        let v = ip.pop_cell();
+
+        ctx.space.set_cell(position, v);
+        position += delta;
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `MODE` fingerprint, toggling modes that change how an IP behaves.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn mode<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4d4f_4445,
+        instructions: &[
+            ('H', hover_mode),
+            ('I', invert_mode),
+            ('Q', queue_mode),
+            ('S', switch_mode),
+        ],
+    }
+}
+
+/// In hover mode, `<`, `>`, `^`, `v`, `_` and `|` add to the delta instead of
+/// replacing it.
+fn hover_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.hover = !ip.hover;
+}
+
+/// In invert mode, values are pushed to the bottom of the stack.
+fn invert_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.stacks.toggle_invert_mode();
+}
+
+/// In queue mode, values are popped off the bottom of the stack.
+fn queue_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.stacks.toggle_queue_mode();
+}
+
+/// In switch mode, `(`, `)`, `[`, `]`, `{` and `}` are replaced by their
+/// counterparts after being executed.
+fn switch_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.switch = !ip.switch;
+}
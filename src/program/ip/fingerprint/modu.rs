@@ -0,0 +1,73 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `MODU` fingerprint, providing alternative modulo operations.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn modu<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4d4f_4455,
+        instructions: &[
+            ('M', signed_rem),
+            ('R', c_rem),
+            ('U', unsigned_rem),
+        ],
+    }
+}
+
+fn signed_rem<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    if b == 0 {
+        ip.push(0);
+    } else {
+        let r = a.wrapping_rem(b);
+
+        if r != 0 && (r < 0) != (b < 0) {
+            ip.push(r + b);
+        } else {
+            ip.push(r);
+        }
+    }
+}
+
+fn c_rem<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    if b == 0 {
+        ip.push(0);
+    } else {
+        ip.push(a.wrapping_rem(b));
+    }
+}
+
+fn unsigned_rem<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    if b == 0 {
+        ip.push(0);
+    } else {
+        ip.push(a.wrapping_rem(b).abs());
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A subset of the `NCRS` fingerprint, providing terminal control in the style
+//! of ncurses.
+//!
+//! Like `TERM`, this writes ANSI escape sequences to the output stream of the
+//! `Config`. The terminal modes set by `E`, `K` and `N` and the `U` instruction
+//! cannot be expressed this way and are not supported.
+
+use crate::data::{Dimension, Value, to_char};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+use super::term::{write_escape, move_cursor};
+
+pub(super) fn ncrs<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4e43_5253,
+        instructions: &[
+            ('B', beep),
+            ('C', clear),
+            ('G', get_char),
+            ('I', initialize),
+            ('M', goto),
+            ('P', put_char),
+            ('R', refresh),
+            ('S', put_string),
+        ],
+    }
+}
+
+fn beep<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    write_escape(ip, ctx, "\x07");
+}
+
+/// Clears the whole screen for 0, the rest of the line for 1 and the rest of
+/// the screen for 2.
+fn clear<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop() {
+        0 => write_escape(ip, ctx, "\x1b[2J\x1b[H"),
+        1 => write_escape(ip, ctx, "\x1b[K"),
+        2 => write_escape(ip, ctx, "\x1b[J"),
+        _ => ip.reflect(),
+    }
+}
+
+/// Switches to the alternate screen for 1, and back for 0.
+fn initialize<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop() {
+        0 => write_escape(ip, ctx, "\x1b[?1049l"),
+        1 => write_escape(ip, ctx, "\x1b[?1049h\x1b[H"),
+        _ => ip.reflect(),
+    }
+}
+
+fn goto<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let y = ip.pop();
+    let x = ip.pop();
+
+    move_cursor(ip, ctx, x, y);
+}
+
+fn put_char<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = ip.pop();
+
+    match to_char(v) {
+        Some(c) if ctx.config.write_char(c) => (),
+        _                                   => ip.reflect(),
+    }
+}
+
+fn put_string<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(s) => write_escape(ip, ctx, &s),
+        None    => ip.reflect(),
+    }
+}
+
+fn get_char<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ctx.config.read_char() {
+        Some(c) => ip.push(c as Value),
+        None    => ip.reflect(),
+    }
+}
+
+fn refresh<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    if !ctx.config.flush_output() {
+        ip.reflect();
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `NULL` fingerprint, which makes every instruction reflect.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn null<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4e55_4c4c,
+        instructions: &[
+            ('A', reflect), ('B', reflect), ('C', reflect), ('D', reflect),
+            ('E', reflect), ('F', reflect), ('G', reflect), ('H', reflect),
+            ('I', reflect), ('J', reflect), ('K', reflect), ('L', reflect),
+            ('M', reflect), ('N', reflect), ('O', reflect), ('P', reflect),
+            ('Q', reflect), ('R', reflect), ('S', reflect), ('T', reflect),
+            ('U', reflect), ('V', reflect), ('W', reflect), ('X', reflect),
+            ('Y', reflect), ('Z', reflect),
+        ],
+    }
+}
+
+fn reflect<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.reflect();
+}
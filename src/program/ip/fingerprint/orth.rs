@@ -0,0 +1,116 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `ORTH` fingerprint, providing instructions of the Orthogonal language.
+
+use crate::data::{Dimension, Point};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn orth<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x4f52_5448,
+        instructions: &[
+            ('A', and),
+            ('E', xor),
+            ('G', get),
+            ('O', or),
+            ('P', put),
+            ('S', output_string),
+            ('V', set_dx),
+            ('W', set_dy),
+            ('X', set_x),
+            ('Y', set_y),
+            ('Z', ramp_if_zero),
+        ],
+    }
+}
+
+fn and<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a & b);
+}
+
+fn or<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a | b);
+}
+
+fn xor<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a ^ b);
+}
+
+/// Pops the position of a cell, the x coordinate first.
+fn pop_position<D: Dimension>(ip: &mut Ip<D>) -> Point {
+    let x = ip.pop() as i32;
+    let y = ip.pop() as i32;
+
+    Point { x: ip.storage.x.wrapping_add(x), y: ip.storage.y.wrapping_add(y), z: ip.storage.z }
+}
+
+fn get<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let position = pop_position(ip);
+
+    ip.push_cell(ctx.space.get_cell(position));
+}
+
+fn put<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let position = pop_position(ip);
+    let v = ip.pop_cell();
+
+    ctx.space.set_cell(position, v);
+}
+
+fn output_string<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(s) => if !ctx.config.write_string(&s) {
+            ip.reflect();
+        },
+        None    => ip.reflect(),
+    }
+}
+
+fn set_dx<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.delta.dx = ip.pop() as i32;
+}
+
+fn set_dy<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.delta.dy = ip.pop() as i32;
+}
+
+fn set_x<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.position.x = ip.pop() as i32;
+}
+
+fn set_y<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.position.y = ip.pop() as i32;
+}
+
+/// Skips the next instruction if the value popped is 0.
+fn ramp_if_zero<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    if ip.pop() == 0 {
+        ip.trampoline(ctx);
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `REFC` fingerprint, referencing vectors by scalar IDs.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn refc<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5245_4643,
+        instructions: &[
+            ('D', dereference),
+            ('R', reference),
+        ],
+    }
+}
+
+fn reference<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let vector = ip.pop_delta();
+
+    ip.push(ctx.references.reference(vector));
+}
+
+fn dereference<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let id = ip.pop();
+
+    match ctx.references.dereference(id) {
+        Some(vector) => ip.push_delta(vector),
+        None         => ip.reflect(),
+    }
+}
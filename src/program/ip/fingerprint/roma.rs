@@ -0,0 +1,66 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `ROMA` fingerprint, which pushes the values of roman numerals.
+
+use crate::data::Dimension;
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn roma<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x524f_4d41,
+        instructions: &[
+            ('C', push_c),
+            ('D', push_d),
+            ('I', push_i),
+            ('L', push_l),
+            ('M', push_m),
+            ('V', push_v),
+            ('X', push_x),
+        ],
+    }
+}
+
+fn push_c<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(100);
+}
+
+fn push_d<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(500);
+}
+
+fn push_i<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(1);
+}
+
+fn push_l<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(50);
+}
+
+fn push_m<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(1000);
+}
+
+fn push_v<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(5);
+}
+
+fn push_x<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.push(10);
+}
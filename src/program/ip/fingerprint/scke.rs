@@ -0,0 +1,51 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `SCKE` fingerprint, extending the `SOCK` fingerprint.
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+use super::sock::address_to_value;
+
+pub(super) fn scke<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5343_4b45,
+        instructions: &[
+            ('H', lookup_host),
+            ('P', peek),
+        ],
+    }
+}
+
+fn lookup_host<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string().and_then(|host| ctx.config.lookup_host(&host)) {
+        Some(addr) => ip.push(address_to_value(addr)),
+        None       => ip.reflect(),
+    }
+}
+
+/// Pushes the number of bytes that can be received without waiting.
+fn peek<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+
+    match ctx.config.peek_socket(s) {
+        Some(n) => ip.push(n as Value),
+        None    => ip.reflect(),
+    }
+}
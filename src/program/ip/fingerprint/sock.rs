@@ -0,0 +1,216 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `SOCK` fingerprint, providing TCP sockets.
+//!
+//! Only IPv4 stream sockets are supported. Addresses are represented by a
+//! single cell holding the address in host byte order, e.g. `0x7f000001` for
+//! `127.0.0.1`. Network access is governed by the `NetworkView` of the
+//! program's `Config`.
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+use crate::data::{Dimension, Value, Delta};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+/// The protocol family of IPv4 sockets.
+const AF_INET: Value = 2;
+/// The socket type of stream sockets.
+const SOCK_STREAM: Value = 2;
+/// The TCP protocol.
+const PROTO_TCP: Value = 1;
+/// The greatest number of bytes received or sent at once.
+const MAX_BYTES: Value = 0x1_0000;
+
+pub(super) fn sock<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x534f_434b,
+        instructions: &[
+            ('A', accept),
+            ('B', bind),
+            ('C', connect),
+            ('I', parse_address),
+            ('K', kill),
+            ('L', listen),
+            ('O', set_option),
+            ('R', receive),
+            ('S', create),
+            ('W', send),
+        ],
+    }
+}
+
+/// Converts an IPv4 address into its representation in a cell.
+pub(super) fn address_to_value(addr: Ipv4Addr) -> Value {
+    Value::from(u32::from(addr) as i32)
+}
+
+/// Pops a socket address, given by the protocol family, port and address.
+fn pop_socket_address<D: Dimension>(ip: &mut Ip<D>) -> Option<SocketAddr> {
+    let addr = ip.pop();
+    let port = ip.pop();
+    let family = ip.pop();
+
+    if family != AF_INET || port < 0 || port > Value::from(u16::MAX) {
+        return None;
+    }
+
+    Some(SocketAddrV4::new(Ipv4Addr::from(addr as u32), port as u16).into())
+}
+
+fn create<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let protocol = ip.pop();
+    let kind = ip.pop();
+    let family = ip.pop();
+
+    if family != AF_INET || kind != SOCK_STREAM || protocol != PROTO_TCP {
+        ip.reflect();
+        return;
+    }
+
+    match ctx.config.create_socket() {
+        Some(s) => ip.push(s),
+        None    => ip.reflect(),
+    }
+}
+
+fn bind<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let addr = pop_socket_address(ip);
+    let s = ip.pop();
+
+    match addr {
+        Some(addr) if ctx.config.bind_socket(s, addr) => (),
+        _                                             => ip.reflect(),
+    }
+}
+
+fn listen<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+    let _backlog = ip.pop();
+
+    if !ctx.config.listen_socket(s) {
+        ip.reflect();
+    }
+}
+
+fn accept<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+
+    match ctx.config.accept_socket(s) {
+        Some((new, SocketAddr::V4(addr))) => {
+            ip.push(Value::from(addr.port()));
+            ip.push(address_to_value(*addr.ip()));
+            ip.push(new);
+        },
+        Some((new, SocketAddr::V6(addr))) => {
+            ip.push(Value::from(addr.port()));
+            ip.push(0);
+            ip.push(new);
+        },
+        None                              => ip.reflect(),
+    }
+}
+
+fn connect<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let addr = pop_socket_address(ip);
+    let s = ip.pop();
+
+    match addr {
+        Some(addr) if ctx.config.connect_socket(s, addr) => (),
+        _                                                => ip.reflect(),
+    }
+}
+
+/// Receives bytes into Funge-space, eastwards from the given vector.
+///
+/// Reflects if more than `MAX_BYTES` bytes are requested.
+fn receive<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+    let n = ip.pop();
+    let position = ip.pop_position();
+
+    if !(0..=MAX_BYTES).contains(&n) {
+        ip.reflect();
+        return;
+    }
+
+    match ctx.config.receive_from_socket(s, n as usize) {
+        Some(data) => {
+            let values: Vec<_> = data.iter().map(|&b| Value::from(b)).collect();
+            let size = Delta { dx: values.len() as i32, dy: 1, dz: 0 };
+
+            ctx.space.set_rect(position, size, &values);
+            ip.push(values.len() as Value);
+        },
+        None       => ip.reflect(),
+    }
+}
+
+/// Sends bytes from Funge-space, read eastwards from the given vector.
+///
+/// Reflects if more than `MAX_BYTES` bytes are to be sent.
+fn send<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+    let n = ip.pop();
+    let position = ip.pop_position();
+
+    if !(0..=MAX_BYTES).contains(&n) {
+        ip.reflect();
+        return;
+    }
+
+    let data: Vec<_> = ctx.space.get_rect(position, Delta { dx: n as i32, dy: 1, dz: 0 })
+        .into_iter()
+        .map(|v| v as u8)
+        .collect();
+
+    match ctx.config.send_to_socket(s, &data) {
+        Some(n) => ip.push(n as Value),
+        None    => ip.reflect(),
+    }
+}
+
+fn kill<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+
+    if !ctx.config.close_socket(s) {
+        ip.reflect();
+    }
+}
+
+/// Accepts the options `SO_DEBUG`, `SO_REUSEADDR`, `SO_KEEPALIVE`,
+/// `SO_DONTROUTE`, `SO_BROADCAST` and `OOBINLINE`, numbered 1 to 6.
+///
+/// The options have no effect, listening sockets always reuse addresses.
+fn set_option<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let s = ip.pop();
+    let option = ip.pop();
+    let _value = ip.pop();
+
+    if !(1..=6).contains(&option) || !ctx.config.has_socket(s) {
+        ip.reflect();
+    }
+}
+
+fn parse_address<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.pop_string().and_then(|s| s.parse().ok()) {
+        Some(addr) => ip.push(address_to_value(addr)),
+        None       => ip.reflect(),
+    }
+}
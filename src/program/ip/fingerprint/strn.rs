@@ -0,0 +1,228 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `STRN` fingerprint, providing operations on 0-terminated strings.
+
+use std::cmp::Ordering;
+
+use crate::data::{Dimension, Value, Point, to_char};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn strn<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5354_524e,
+        instructions: &[
+            ('A', append),
+            ('C', compare),
+            ('D', display),
+            ('F', search),
+            ('G', get),
+            ('I', input),
+            ('L', left),
+            ('M', middle),
+            ('N', length),
+            ('P', put),
+            ('R', right),
+            ('S', to_string),
+            ('V', parse),
+        ],
+    }
+}
+
+fn append<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match (ip.pop_string(), ip.pop_string()) {
+        (Some(a), Some(b)) => {
+            ip.push_string(&(a + &b));
+        },
+        _                  => ip.reflect(),
+    }
+}
+
+fn compare<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match (ip.pop_string(), ip.pop_string()) {
+        (Some(b), Some(a)) => {
+            let v = match a.cmp(&b) {
+                Ordering::Less    => -1,
+                Ordering::Equal   => 0,
+                Ordering::Greater => 1,
+            };
+
+            ip.push(v);
+        },
+        _                  => ip.reflect(),
+    }
+}
+
+fn display<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(s) => if !ctx.config.write_string(&s) {
+            ip.reflect();
+        },
+        None    => ip.reflect(),
+    }
+}
+
+fn search<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match (ip.pop_string(), ip.pop_string()) {
+        (Some(haystack), Some(needle)) => {
+            match haystack.find(&needle) {
+                Some(i) => ip.push_string(&haystack[i..]),
+                None    => ip.push_string(""),
+            };
+        },
+        _                              => ip.reflect(),
+    }
+}
+
+fn get<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let Point { mut x, y, z } = ip.pop_position();
+    let max_x = ctx.space.max().x;
+    let mut s = String::new();
+
+    loop {
+        let v = ctx.space.get(Point { x, y, z });
+
+        if v == 0 {
+            break;
+        }
+
+        match to_char(v) {
+            Some(c) if x <= max_x => s.push(c),
+            _                     => {
+                ip.reflect();
+                return;
+            },
+        }
+
+        x = x.wrapping_add(1);
+    }
+
+    ip.push_string(&s);
+}
+
+fn input<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ctx.config.read_line() {
+        Some(s) => {
+            ip.push_string(&s);
+        },
+        None    => ip.reflect(),
+    }
+}
+
+fn left<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    match ip.pop_string() {
+        Some(s) if n >= 0 => {
+            let s: String = s.chars().take(n as usize).collect();
+            ip.push_string(&s);
+        },
+        _                 => ip.reflect(),
+    }
+}
+
+fn right<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    match ip.pop_string() {
+        Some(s) if n >= 0 => {
+            let len = s.chars().count();
+            let s: String = s.chars().skip(len.saturating_sub(n as usize)).collect();
+            ip.push_string(&s);
+        },
+        _                 => ip.reflect(),
+    }
+}
+
+fn middle<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+    let start = ip.pop();
+
+    match ip.pop_string() {
+        Some(s) if n >= 0 && start >= 0 => {
+            let s: String = s.chars().skip(start as usize).take(n as usize).collect();
+            ip.push_string(&s);
+        },
+        _                               => ip.reflect(),
+    }
+}
+
+fn length<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(s) => {
+            ip.push_string(&s);
+            ip.push(s.chars().count() as Value);
+        },
+        None    => ip.reflect(),
+    }
+}
+
+fn put<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let Point { mut x, y, z } = ip.pop_position();
+
+    match ip.pop_string() {
+        Some(s) => {
+            for c in s.chars() {
+                ctx.space.set(Point { x, y, z }, c as Value);
+                x = x.wrapping_add(1);
+            }
+
+            ctx.space.set(Point { x, y, z }, 0);
+        },
+        None    => ip.reflect(),
+    }
+}
+
+fn to_string<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push_string(&v.to_string());
+}
+
+fn parse<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.pop_string() {
+        Some(s) => ip.push(atoi(&s)),
+        None    => ip.reflect(),
+    }
+}
+
+/// Parses the integer at the start of a string, like C's `atoi`.
+///
+/// Leading whitespace is skipped. Parsing stops at the first character that
+/// is not a digit. If no digits are found, 0 is returned.
+fn atoi(s: &str) -> Value {
+    let s = s.trim_start();
+    let (negative, digits) = match s.chars().next() {
+        Some('-') => (true, &s[1..]),
+        Some('+') => (false, &s[1..]),
+        _         => (false, s),
+    };
+
+    let mut n: Value = 0;
+
+    for d in digits.chars().map_while(|c| c.to_digit(10)) {
+        n = n.wrapping_mul(10).wrapping_add(d as Value);
+    }
+
+    if negative {
+        n.wrapping_neg()
+    } else {
+        n
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `SUBR` fingerprint, providing subroutines.
+//!
+//! A call pushes the position and delta of the calling IP below the arguments
+//! of the subroutine, a return restores them. In relative mode, the addresses
+//! of subroutines are relative to the storage offset.
+//!
+//! The arguments are moved on the stack in place. Like cells popped off an
+//! empty stack, missing arguments are zeros.
+
+use std::convert::TryFrom;
+
+use crate::data::{Dimension, Value, Point, Delta};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn subr<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5355_4252,
+        instructions: &[
+            ('A', absolute_mode),
+            ('C', call),
+            ('J', jump),
+            ('O', relative_mode),
+            ('R', ret),
+        ],
+    }
+}
+
+/// Checks the number of arguments of a call or return, which lie on top of
+/// `below` other cells.
+///
+/// Returns `None` if it is negative or if there is not enough memory to fill
+/// up the stack with zeros for the missing cells.
+fn arguments<D: Dimension>(ip: &mut Ip<D>, n: Value, below: usize) -> Option<usize> {
+    let n = usize::try_from(n).ok()?;
+    let missing = n.saturating_add(below).saturating_sub(ip.stacks.len());
+
+    if ip.stacks.reserve(missing) {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+/// Pops the address of a subroutine.
+fn pop_address<D: Dimension>(ip: &mut Ip<D>) -> Point {
+    let vector = ip.pop_delta();
+
+    if ip.relative_calls {
+        ip.storage + vector
+    } else {
+        Point { x: vector.dx, y: vector.dy, z: vector.dz }
+    }
+}
+
+/// Moves the IP east so that it will execute the given address next.
+fn go_to<D: Dimension>(ip: &mut Ip<D>, address: Point) {
+    let delta = Delta { dx: 1, dy: 0, dz: 0 };
+
+    ip.position = address - delta;
+    ip.set_delta(delta);
+}
+
+fn call<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+    let address = pop_address(ip);
+
+    let n = match arguments(ip, n, 0) {
+        Some(n) => n,
+        None    => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    let len = ip.stacks.len();
+    let Point { x, y, z } = ip.position;
+
+    ip.push_delta(Delta { dx: x, dy: y, dz: z });
+    ip.push_delta(ip.delta);
+    ip.stacks.rotate(ip.stacks.len() - len, n);
+
+    go_to(ip, address);
+}
+
+fn jump<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let address = pop_address(ip);
+
+    go_to(ip, address);
+}
+
+fn ret<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+
+    let n = match arguments(ip, n, 2 * D::COUNT) {
+        Some(n) => n,
+        None    => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    ip.stacks.rotate(n, 2 * D::COUNT);
+
+    let delta = ip.pop_delta();
+    let Delta { dx: x, dy: y, dz: z } = ip.pop_delta();
+
+    ip.position = Point { x, y, z };
+    ip.set_delta(delta);
+}
+
+fn absolute_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.relative_calls = false;
+}
+
+fn relative_mode<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.relative_calls = true;
+}
@@ -0,0 +1,100 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `TERM` fingerprint, providing terminal control.
+//!
+//! The terminal is controlled by writing ANSI escape sequences to the output
+//! stream of the `Config`. Cursor positions start at 0.
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn term<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5445_524d,
+        instructions: &[
+            ('C', clear_screen),
+            ('D', cursor_down),
+            ('G', goto),
+            ('H', home),
+            ('L', clear_to_eol),
+            ('S', clear_to_eos),
+            ('U', cursor_up),
+        ],
+    }
+}
+
+/// Writes an escape sequence, reflecting if it fails.
+pub(super) fn write_escape<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>, escape: &str) {
+    if !ctx.config.write_string(escape) {
+        ip.reflect();
+    }
+}
+
+/// Moves the cursor to the position `(x, y)`.
+pub(super) fn move_cursor<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>, x: Value, y: Value) {
+    if x < 0 || y < 0 {
+        ip.reflect();
+    } else {
+        write_escape(ip, ctx, &format!("\x1b[{};{}H", y + 1, x + 1));
+    }
+}
+
+fn clear_screen<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    write_escape(ip, ctx, "\x1b[2J\x1b[H");
+}
+
+fn home<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    write_escape(ip, ctx, "\x1b[H");
+}
+
+fn goto<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let y = ip.pop();
+    let x = ip.pop();
+
+    move_cursor(ip, ctx, x, y);
+}
+
+fn cursor_up<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+
+    if n > 0 {
+        write_escape(ip, ctx, &format!("\x1b[{}A", n));
+    } else if n < 0 {
+        write_escape(ip, ctx, &format!("\x1b[{}B", n.unsigned_abs()));
+    }
+}
+
+fn cursor_down<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+
+    if n > 0 {
+        write_escape(ip, ctx, &format!("\x1b[{}B", n));
+    } else if n < 0 {
+        write_escape(ip, ctx, &format!("\x1b[{}A", n.unsigned_abs()));
+    }
+}
+
+fn clear_to_eol<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    write_escape(ip, ctx, "\x1b[K");
+}
+
+fn clear_to_eos<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    write_escape(ip, ctx, "\x1b[J");
+}
@@ -0,0 +1,281 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `3DSP` fingerprint, providing 3D vector and matrix operations.
+//!
+//! All numbers are single precision floating point numbers, as used by the
+//! `FPSP` fingerprint. A 3D vector occupies three cells, with z on top. A
+//! matrix is a 4x4 block in Funge-space, addressed relative to the storage
+//! offset, whose rows extend eastwards and follow each other southwards.
+//! Vectors are transformed as columns `(x, y, z, 1)`.
+
+use crate::data::{Dimension, Point, Delta};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+use super::float::{Encoding, Single};
+
+type Vector = [f64; 3];
+type Matrix = [[f64; 4]; 4];
+
+/// The size of a matrix in Funge-space.
+const MATRIX_SIZE: Delta = Delta { dx: 4, dy: 4, dz: 0 };
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+pub(super) fn three_dsp<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x3344_5350,
+        instructions: &[
+            ('A', add),
+            ('B', sub),
+            ('C', cross),
+            ('D', dot),
+            ('L', length),
+            ('M', mul),
+            ('N', normalize),
+            ('P', copy_matrix),
+            ('R', rotation_matrix),
+            ('S', scale_matrix),
+            ('T', translation_matrix),
+            ('U', duplicate),
+            ('V', map_to_view),
+            ('X', transform),
+            ('Y', mul_matrices),
+            ('Z', scale),
+        ],
+    }
+}
+
+fn pop_vector<D: Dimension>(ip: &mut Ip<D>) -> Vector {
+    let z = Single::pop(ip);
+    let y = Single::pop(ip);
+    let x = Single::pop(ip);
+
+    [x, y, z]
+}
+
+fn push_vector<D: Dimension>(ip: &mut Ip<D>, v: Vector) {
+    for &c in &v {
+        Single::push(ip, c);
+    }
+}
+
+fn read_matrix<D: Dimension>(ctx: &Context<D>, position: Point) -> Matrix {
+    let cells = ctx.space.get_rect(position, MATRIX_SIZE);
+    let mut m = [[0.0; 4]; 4];
+
+    for (i, &v) in cells.iter().enumerate() {
+        m[i / 4][i % 4] = Single::from_cell(v);
+    }
+
+    m
+}
+
+fn write_matrix<D: Dimension>(ctx: &mut Context<D>, position: Point, m: &Matrix) {
+    let cells: Vec<_> = m.iter().flatten().map(|&x| Single::to_cell(x)).collect();
+
+    ctx.space.set_rect(position, MATRIX_SIZE, &cells);
+}
+
+fn mul_matrix(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut m = [[0.0; 4]; 4];
+
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+
+    m
+}
+
+fn add<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = pop_vector(ip);
+    let a = pop_vector(ip);
+
+    push_vector(ip, [a[0] + b[0], a[1] + b[1], a[2] + b[2]]);
+}
+
+fn sub<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = pop_vector(ip);
+    let a = pop_vector(ip);
+
+    push_vector(ip, [a[0] - b[0], a[1] - b[1], a[2] - b[2]]);
+}
+
+fn mul<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = pop_vector(ip);
+    let a = pop_vector(ip);
+
+    push_vector(ip, [a[0] * b[0], a[1] * b[1], a[2] * b[2]]);
+}
+
+fn cross<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = pop_vector(ip);
+    let a = pop_vector(ip);
+
+    push_vector(ip, [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]);
+}
+
+fn dot<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = pop_vector(ip);
+    let a = pop_vector(ip);
+
+    Single::push(ip, a[0] * b[0] + a[1] * b[1] + a[2] * b[2]);
+}
+
+fn length<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let [x, y, z] = pop_vector(ip);
+
+    Single::push(ip, (x * x + y * y + z * z).sqrt());
+}
+
+/// Scales a vector to length 1. The zero vector is left unchanged.
+fn normalize<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let [x, y, z] = pop_vector(ip);
+    let len = (x * x + y * y + z * z).sqrt();
+
+    if len == 0.0 {
+        push_vector(ip, [x, y, z]);
+    } else {
+        push_vector(ip, [x / len, y / len, z / len]);
+    }
+}
+
+fn scale<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let [x, y, z] = pop_vector(ip);
+    let n = Single::pop(ip);
+
+    push_vector(ip, [x * n, y * n, z * n]);
+}
+
+fn duplicate<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = pop_vector(ip);
+
+    push_vector(ip, v);
+    push_vector(ip, v);
+}
+
+/// Projects a point onto the plane `z = 1`. Points with `z = 0` are left
+/// unchanged.
+fn map_to_view<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let [x, y, z] = pop_vector(ip);
+
+    if z == 0.0 {
+        Single::push(ip, x);
+        Single::push(ip, y);
+    } else {
+        Single::push(ip, x / z);
+        Single::push(ip, y / z);
+    }
+}
+
+fn copy_matrix<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let source = ip.pop_position();
+    let target = ip.pop_position();
+    let cells = ctx.space.get_rect(source, MATRIX_SIZE);
+
+    ctx.space.set_rect(target, MATRIX_SIZE, &cells);
+}
+
+/// Generates a rotation matrix around the x, y or z axis, given as 1, 2 or 3.
+///
+/// The angle is given in degrees.
+fn rotation_matrix<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let angle = Single::pop(ip);
+    let axis = ip.pop();
+    let target = ip.pop_position();
+
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (i, j) = match axis {
+        1 => (1, 2),
+        2 => (2, 0),
+        3 => (0, 1),
+        _ => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    let mut m = IDENTITY;
+
+    m[i][i] = cos;
+    m[i][j] = -sin;
+    m[j][i] = sin;
+    m[j][j] = cos;
+
+    write_matrix(ctx, target, &m);
+}
+
+fn scale_matrix<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = pop_vector(ip);
+    let target = ip.pop_position();
+    let mut m = IDENTITY;
+
+    for (i, &c) in v.iter().enumerate() {
+        m[i][i] = c;
+    }
+
+    write_matrix(ctx, target, &m);
+}
+
+fn translation_matrix<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = pop_vector(ip);
+    let target = ip.pop_position();
+    let mut m = IDENTITY;
+
+    for (i, &c) in v.iter().enumerate() {
+        m[i][3] = c;
+    }
+
+    write_matrix(ctx, target, &m);
+}
+
+/// Multiplies a vector by a matrix, dividing by the resulting w component
+/// unless it is 0.
+fn transform<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let source = ip.pop_position();
+    let [x, y, z] = pop_vector(ip);
+    let m = read_matrix(ctx, source);
+
+    let v: Vec<_> = m.iter()
+        .map(|row| row[0] * x + row[1] * y + row[2] * z + row[3])
+        .collect();
+    let w = if v[3] == 0.0 { 1.0 } else { v[3] };
+
+    push_vector(ip, [v[0] / w, v[1] / w, v[2] / w]);
+}
+
+fn mul_matrices<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let b = ip.pop_position();
+    let a = ip.pop_position();
+    let target = ip.pop_position();
+
+    let m = mul_matrix(&read_matrix(ctx, a), &read_matrix(ctx, b));
+
+    write_matrix(ctx, target, &m);
+}
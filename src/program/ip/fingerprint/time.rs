@@ -0,0 +1,113 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `TIME` fingerprint, providing the current date and time.
+//!
+//! Each IP reports either local time, which is the default, or UTC.
+
+use chrono::{Datelike, Local, NaiveDateTime, Timelike, Utc};
+
+use crate::data::{Dimension, Value};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn time<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5449_4d45,
+        instructions: &[
+            ('D', day),
+            ('F', day_of_year),
+            ('G', use_utc),
+            ('H', hour),
+            ('L', use_local),
+            ('M', minute),
+            ('O', month),
+            ('S', second),
+            ('W', day_of_week),
+            ('Y', year),
+        ],
+    }
+}
+
+/// Returns the current date and time, in UTC or local time.
+fn now<D: Dimension>(ip: &Ip<D>) -> NaiveDateTime {
+    if ip.utc_time {
+        Utc::now().naive_utc()
+    } else {
+        Local::now().naive_local()
+    }
+}
+
+fn use_utc<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.utc_time = true;
+}
+
+fn use_local<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.utc_time = false;
+}
+
+fn year<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).year();
+
+    ip.push(Value::from(v));
+}
+
+/// Pushes the month, starting with 1 for January.
+fn month<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).month();
+
+    ip.push(v as Value);
+}
+
+fn day<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).day();
+
+    ip.push(v as Value);
+}
+
+/// Pushes the day of the year, starting with 0 for January 1.
+fn day_of_year<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).ordinal0();
+
+    ip.push(v as Value);
+}
+
+/// Pushes the day of the week, starting with 1 for Sunday.
+fn day_of_week<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).weekday().number_from_sunday();
+
+    ip.push(v as Value);
+}
+
+fn hour<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).hour();
+
+    ip.push(v as Value);
+}
+
+fn minute<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).minute();
+
+    ip.push(v as Value);
+}
+
+fn second<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = now(ip).second();
+
+    ip.push(v as Value);
+}
@@ -0,0 +1,476 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `TOYS` fingerprint, a collection of assorted "standard toys".
+//!
+//! Blocks of Funge-space are addressed relative to the storage offset and read
+//! and written rectangle by rectangle. Copies and moves between overlapping
+//! blocks are instead performed cell by cell, in the order of increasing
+//! coordinates for the low-order variants and decreasing coordinates for the
+//! high-order ones. Blocks extend along every dimension of the space, so they
+//! are single rows in Unefunge and can span several planes in Trefunge.
+
+use std::convert::TryFrom;
+
+use crate::data::{Dimension, Value, Point, Delta, SPACE};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+/// The greatest number of cells read or written as a single rectangle.
+const CHUNK: i32 = 4096;
+
+pub(super) fn toys<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x544f_5953,
+        instructions: &[
+            ('A', gable),
+            ('B', pair_of_shoes),
+            ('C', bracelet),
+            ('D', toilet_seat),
+            ('E', pitchfork_head),
+            ('F', calipers),
+            ('G', counterclockwise),
+            ('H', pair_of_stilts),
+            ('I', doric_column),
+            ('J', fishhook),
+            ('K', scissors),
+            ('L', corner),
+            ('M', kittycat),
+            ('N', lightning_bolt),
+            ('O', boulder),
+            ('P', mailbox),
+            ('Q', necklace),
+            ('R', can_opener),
+            ('S', chicane),
+            ('T', barstool),
+            ('U', tumbler),
+            ('V', dixiecup),
+            ('W', television_antenna),
+            ('X', buried_treasure),
+            ('Y', slingshot),
+            ('Z', barn_door),
+        ],
+    }
+}
+
+/// The order in which the cells of a block are transferred.
+#[derive(Clone, Copy, PartialEq)]
+enum Order {
+    Low,
+    High,
+}
+
+/// Pushes `n` copies of a value.
+fn gable<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let n = ip.pop();
+    let v = ip.pop();
+
+    if n < 0 {
+        ip.reflect();
+    } else {
+        for _ in 0..n {
+            ip.push(v);
+        }
+    }
+}
+
+/// Pushes the sum and the difference of two values.
+fn pair_of_shoes<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    ip.push(a.wrapping_add(b));
+    ip.push(a.wrapping_sub(b));
+}
+
+fn bracelet<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    transfer(ip, ctx, Order::Low, false);
+}
+
+fn toilet_seat<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.wrapping_sub(1));
+}
+
+/// Replaces the contents of the stack by their sum.
+fn pitchfork_head<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let mut sum: Value = 0;
+
+    for _ in 0..ip.stacks.len() {
+        sum = sum.wrapping_add(ip.pop());
+    }
+
+    ip.push(sum);
+}
+
+/// Pops a matrix of values into Funge-space.
+///
+/// The first value popped is placed in the northeast corner, the rows are
+/// filled from east to west.
+fn calipers<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = ip.pop_position();
+    let height = ip.pop();
+    let width = ip.pop();
+
+    let size = match matrix_size(width, height) {
+        Some(size) => size,
+        None       => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    for (offset, rect) in rects(size) {
+        let mut values = vec![0; rect.dx as usize * rect.dy as usize];
+
+        for row in values.chunks_mut(rect.dx as usize) {
+            for v in row.iter_mut().rev() {
+                *v = ip.pop();
+            }
+        }
+
+        ctx.space.set_rect(target + offset, rect, &values);
+    }
+}
+
+/// Pushes a matrix of values from Funge-space, the inverse of `F`.
+fn counterclockwise<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let source = ip.pop_position();
+    let height = ip.pop();
+    let width = ip.pop();
+
+    let size = match matrix_size(width, height) {
+        Some(size) => size,
+        None       => {
+            ip.reflect();
+            return;
+        },
+    };
+
+    for (offset, rect) in rects(size).rev() {
+        let values = ctx.space.get_rect(source + offset, rect);
+
+        for row in values.chunks(rect.dx as usize).rev() {
+            for &v in row {
+                ip.push(v);
+            }
+        }
+    }
+}
+
+/// Returns the size of a matrix `width` cells wide and `height` cells high.
+///
+/// Returns `None` if either extent is negative or does not fit into a
+/// coordinate.
+fn matrix_size(width: Value, height: Value) -> Option<Delta> {
+    let dx = i32::try_from(width).ok().filter(|&dx| dx >= 0)?;
+    let dy = i32::try_from(height).ok().filter(|&dy| dy >= 0)?;
+
+    Some(Delta { dx, dy, dz: 1 })
+}
+
+/// Shifts a value left, or right for a negative shift.
+fn pair_of_stilts<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let b = ip.pop();
+    let a = ip.pop();
+
+    let v = if b >= 0 {
+        u32::try_from(b).ok().and_then(|s| a.checked_shl(s)).unwrap_or(0)
+    } else {
+        u32::try_from(b.unsigned_abs()).ok().and_then(|s| a.checked_shr(s)).unwrap_or(a >> 63)
+    };
+
+    ip.push(v);
+}
+
+fn doric_column<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.wrapping_add(1));
+}
+
+/// Translates the column of the IP southwards.
+fn fishhook<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let min = ctx.space.min();
+    let max = ctx.space.max();
+
+    let source = Point { y: min.y, ..ip.position };
+    let size = Delta { dx: 1, dy: max.y - min.y + 1, dz: 1 };
+
+    translate(ctx, source, size, Delta { dx: 0, dy: n as i32, dz: 0 });
+}
+
+fn scissors<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    transfer(ip, ctx, Order::High, false);
+}
+
+/// Pushes the value of the cell to the left of the IP.
+fn corner<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = ctx.space.get(ip.position + ip.delta.rotate_left());
+
+    ip.push(v);
+}
+
+fn kittycat<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    transfer(ip, ctx, Order::Low, true);
+}
+
+fn lightning_bolt<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let v = ip.pop();
+
+    ip.push(v.wrapping_neg());
+}
+
+/// Translates the row of the IP eastwards.
+fn boulder<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let n = ip.pop();
+    let min = ctx.space.min();
+    let max = ctx.space.max();
+
+    let source = Point { x: min.x, ..ip.position };
+    let size = Delta { dx: max.x - min.x + 1, dy: 1, dz: 1 };
+
+    translate(ctx, source, size, Delta { dx: n as i32, dy: 0, dz: 0 });
+}
+
+/// Replaces the contents of the stack by their product.
+fn mailbox<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    let mut product: Value = 1;
+
+    for _ in 0..ip.stacks.len() {
+        product = product.wrapping_mul(ip.pop());
+    }
+
+    ip.push(product);
+}
+
+/// Puts a value into the cell behind the IP.
+fn necklace<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = ip.pop();
+
+    ctx.space.set(ip.position - ip.delta, v);
+}
+
+/// Pushes the value of the cell to the right of the IP.
+fn can_opener<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let v = ctx.space.get(ip.position + ip.delta.rotate_right());
+
+    ip.push(v);
+}
+
+/// Fills a block of Funge-space with a value.
+fn chicane<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let target = ip.pop_position();
+    let size = ip.pop_size();
+    let v = ip.pop();
+
+    if size.dx < 0 || size.dy < 0 || size.dz < 0 {
+        ip.reflect();
+        return;
+    }
+
+    for (offset, rect) in rects(size) {
+        let values = vec![v; rect.dx as usize * rect.dy as usize];
+
+        ctx.space.set_rect(target + offset, rect, &values);
+    }
+}
+
+/// Acts as `_`, `|` or `m`, depending on the dimension popped.
+fn barstool<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    match ip.pop() {
+        0                      => ip.if_east_west(),
+        1 if !ip.is_unefunge() => ip.if_north_south(),
+        2 if ip.is_trefunge()  => ip.if_high_low(),
+        _                      => ip.reflect(),
+    }
+}
+
+/// Goes in a random direction and replaces itself with the matching arrow.
+fn tumbler<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    ip.randomize_delta();
+
+    let arrow = match ip.delta {
+        Delta { dx:  1, .. } => '>',
+        Delta { dx: -1, .. } => '<',
+        Delta { dy:  1, .. } => 'v',
+        Delta { dy: -1, .. } => '^',
+        Delta { dz:  1, .. } => 'h',
+        _                    => 'l',
+    };
+
+    ctx.space.set(ip.position, arrow as Value);
+}
+
+fn dixiecup<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    transfer(ip, ctx, Order::High, true);
+}
+
+/// Waits until the cell at a vector is no longer less than a value.
+///
+/// Reflects if the cell is greater than the value.
+fn television_antenna<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let vector = ip.pop_delta();
+    let v = ip.pop();
+    let cell = ctx.space.get(ip.storage + vector);
+
+    if cell < v {
+        ip.push(v);
+        ip.push_delta(vector);
+        ip.position -= ip.delta;
+    } else if cell > v {
+        ip.reflect();
+    }
+}
+
+fn buried_treasure<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.position.x = ip.position.x.wrapping_add(1);
+}
+
+fn slingshot<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    ip.position.y = ip.position.y.wrapping_add(1);
+}
+
+/// Moves one cell high in Trefunge, reflects otherwise.
+fn barn_door<D: Dimension>(ip: &mut Ip<D>, _: &mut Context<D>) {
+    if ip.is_trefunge() {
+        ip.position.z = ip.position.z.wrapping_add(1);
+    } else {
+        ip.reflect();
+    }
+}
+
+/// Copies or moves a block of Funge-space.
+///
+/// Pops the target, then the size, then the source of the block.
+fn transfer<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>, order: Order, clear: bool) {
+    let target = ip.pop_position();
+    let size = ip.pop_size();
+    let source = ip.pop_position();
+
+    if size.dx < 0 || size.dy < 0 || size.dz < 0 {
+        ip.reflect();
+        return;
+    }
+
+    transfer_block(ctx, source, target, size, order, clear);
+}
+
+/// Moves a block of Funge-space by the given delta.
+///
+/// The cells are moved starting from the side the block moves towards, so
+/// that none is overwritten before it has been moved.
+fn translate<D: Dimension>(ctx: &mut Context<D>, source: Point, size: Delta, delta: Delta) {
+    let order = if delta.dx > 0 || delta.dy > 0 {
+        Order::High
+    } else {
+        Order::Low
+    };
+
+    transfer_block(ctx, source, source + delta, size, order, true);
+}
+
+/// Copies a block of Funge-space, replacing the source block by spaces if
+/// `clear` is set.
+///
+/// Overlapping blocks are copied cell by cell in the given order, each source
+/// cell being cleared before the target cell is written.
+fn transfer_block<D: Dimension>(
+    ctx: &mut Context<D>,
+    source: Point,
+    target: Point,
+    size: Delta,
+    order: Order,
+    clear: bool,
+) {
+    if !overlap(source, target, size) {
+        for (offset, rect) in rects(size) {
+            let values = ctx.space.get_rect(source + offset, rect);
+
+            if clear {
+                let spaces = vec![SPACE; values.len()];
+
+                ctx.space.set_rect(source + offset, rect, &spaces);
+            }
+
+            ctx.space.set_rect(target + offset, rect, &values);
+        }
+
+        return;
+    }
+
+    let mut batch = ctx.space.batch();
+
+    for d in offsets(size, order) {
+        let v = batch.get(source + d);
+
+        if clear {
+            batch.set(source + d, SPACE);
+        }
+
+        batch.set(target + d, v);
+    }
+}
+
+/// Returns the offsets of the cells of a block of the given size, row by row
+/// and plane by plane in the given order.
+fn offsets(size: Delta, order: Order) -> Box<dyn Iterator<Item = Delta>> {
+    let offsets = (0..size.dz).flat_map(move |dz| {
+        (0..size.dy).flat_map(move |dy| (0..size.dx).map(move |dx| Delta { dx, dy, dz }))
+    });
+
+    match order {
+        Order::Low  => Box::new(offsets),
+        Order::High => Box::new(offsets.rev()),
+    }
+}
+
+/// Splits a block of the given size into rectangles of at most `CHUNK` cells
+/// and returns the offset and size of each.
+///
+/// The rectangles lie in a single plane. They are listed plane by plane and
+/// row by row, and from east to west within a row.
+fn rects(size: Delta) -> impl DoubleEndedIterator<Item = (Delta, Delta)> {
+    let width = size.dx.clamp(1, CHUNK);
+    let height = CHUNK / width;
+
+    (0..size.dz).flat_map(move |dz| {
+        (0..size.dy).step_by(height as usize).flat_map(move |dy| {
+            (0..size.dx).step_by(width as usize).rev().map(move |dx| {
+                let rect = Delta {
+                    dx: width.min(size.dx - dx),
+                    dy: height.min(size.dy - dy),
+                    dz: 1,
+                };
+
+                (Delta { dx, dy, dz }, rect)
+            })
+        })
+    })
+}
+
+/// Checks whether the blocks of the given size at `a` and `b` overlap.
+fn overlap(a: Point, b: Point, size: Delta) -> bool {
+    let overlap = |a: i32, b: i32, len: i32| {
+        (i64::from(a) - i64::from(b)).abs() < i64::from(len)
+    };
+
+    overlap(a.x, b.x, size.dx) && overlap(a.y, b.y, size.dy) && overlap(a.z, b.z, size.dz)
+}
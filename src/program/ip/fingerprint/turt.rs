@@ -0,0 +1,269 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The `TURT` fingerprint, providing turtle graphics.
+//!
+//! The turtle draws onto an in-memory drawing shared by all IPs, which the `I`
+//! instruction renders as SVG to the drawing output of the `Config`. Headings
+//! are given in degrees, clockwise from east, and the y axis points south, as
+//! in Funge-space.
+
+use std::fmt::Write;
+
+use crate::data::{Dimension, Value, from_float};
+use crate::program::Context;
+use crate::program::ip::Ip;
+use super::Builtin;
+
+pub(super) fn turt<D: Dimension>() -> Builtin<D> {
+    Builtin {
+        id: 0x5455_5254,
+        instructions: &[
+            ('A', query_heading),
+            ('B', back),
+            ('C', pen_colour),
+            ('D', show_display),
+            ('E', query_pen),
+            ('F', forward),
+            ('H', set_heading),
+            ('I', print_drawing),
+            ('L', turn_left),
+            ('N', clear_paper),
+            ('P', pen_position),
+            ('Q', query_position),
+            ('R', turn_right),
+            ('T', teleport),
+            ('U', query_bounds),
+        ],
+    }
+}
+
+/// A line drawn by the turtle.
+#[derive(Clone, Copy)]
+struct Line {
+    from: (Value, Value),
+    to: (Value, Value),
+    colour: Value,
+}
+
+/// The state of the turtle and the drawing it has made.
+pub(crate) struct Turtle {
+    x: f64,
+    y: f64,
+    heading: f64,
+    pen_down: bool,
+    colour: Value,
+    paper: Value,
+    visible: bool,
+    lines: Vec<Line>,
+}
+
+impl Default for Turtle {
+    fn default() -> Self {
+        Turtle {
+            x: 0.0,
+            y: 0.0,
+            heading: 0.0,
+            pen_down: false,
+            colour: 0x00_0000,
+            paper: 0xff_ffff,
+            visible: false,
+            lines: Vec::new(),
+        }
+    }
+}
+
+impl Turtle {
+    /// Returns the position of the turtle, rounded to whole pixels.
+    fn position(&self) -> (Value, Value) {
+        (from_float(self.x.round()), from_float(self.y.round()))
+    }
+
+    /// Moves the turtle to the given position, drawing a line if the pen is
+    /// down.
+    fn move_to(&mut self, x: f64, y: f64) {
+        let from = self.position();
+
+        self.x = x;
+        self.y = y;
+
+        if self.pen_down {
+            let to = self.position();
+
+            self.lines.push(Line { from, to, colour: self.colour });
+        }
+    }
+
+    /// Moves the turtle along its heading.
+    fn advance(&mut self, distance: Value) {
+        let (sin, cos) = self.heading.to_radians().sin_cos();
+        let d = distance as f64;
+
+        self.move_to(self.x + d * cos, self.y + d * sin);
+    }
+
+    /// Returns the northwest and southeast corners of the drawing.
+    ///
+    /// If nothing has been drawn yet, both are the position of the turtle.
+    fn bounds(&self) -> ((Value, Value), (Value, Value)) {
+        let mut points = self.lines.iter().flat_map(|l| [l.from, l.to]);
+        let first = points.next().unwrap_or_else(|| self.position());
+
+        points.fold((first, first), |((x0, y0), (x1, y1)), (x, y)| {
+            ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))
+        })
+    }
+
+    /// Renders the drawing as an SVG document.
+    fn to_svg(&self) -> String {
+        let ((x0, y0), (x1, y1)) = self.bounds();
+        let (w, h) = (x1 - x0 + 2, y1 - y0 + 2);
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+            x0 - 1, y0 - 1, w, h,
+        );
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            x0 - 1, y0 - 1, w, h, colour(self.paper),
+        );
+
+        for line in &self.lines {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-linecap=\"round\"/>",
+                line.from.0, line.from.1, line.to.0, line.to.1, colour(line.colour),
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Formats a 24-bit RGB colour for SVG.
+fn colour(rgb: Value) -> String {
+    format!("#{:06x}", rgb & 0xff_ffff)
+}
+
+fn turn_left<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let d = ip.pop();
+
+    ctx.turtle.heading -= d as f64;
+}
+
+fn turn_right<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let d = ip.pop();
+
+    ctx.turtle.heading += d as f64;
+}
+
+fn set_heading<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let h = ip.pop();
+
+    ctx.turtle.heading = h as f64;
+}
+
+fn forward<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let d = ip.pop();
+
+    ctx.turtle.advance(d);
+}
+
+fn back<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let d = ip.pop();
+
+    ctx.turtle.advance(d.wrapping_neg());
+}
+
+/// Lifts the pen for 0, puts it down for 1.
+fn pen_position<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop() {
+        0 => ctx.turtle.pen_down = false,
+        1 => ctx.turtle.pen_down = true,
+        _ => ip.reflect(),
+    }
+}
+
+fn pen_colour<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    ctx.turtle.colour = ip.pop();
+}
+
+/// Erases the drawing and fills the paper with a colour.
+fn clear_paper<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    ctx.turtle.paper = ip.pop();
+    ctx.turtle.lines.clear();
+}
+
+/// Shows the display for 1, hides it for 0.
+///
+/// The drawing is never displayed while the program runs, but only rendered
+/// by `I`.
+fn show_display<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    match ip.pop() {
+        0 => ctx.turtle.visible = false,
+        1 => ctx.turtle.visible = true,
+        _ => ip.reflect(),
+    }
+}
+
+/// Moves the turtle to a position without drawing.
+fn teleport<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let y = ip.pop();
+    let x = ip.pop();
+
+    ctx.turtle.x = x as f64;
+    ctx.turtle.y = y as f64;
+}
+
+fn query_pen<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    ip.push(ctx.turtle.pen_down as Value);
+}
+
+/// Pushes the heading, normalized to the range 0 to 359.
+fn query_heading<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let h = ctx.turtle.heading.round().rem_euclid(360.0);
+
+    ip.push(h as Value);
+}
+
+fn query_position<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let (x, y) = ctx.turtle.position();
+
+    ip.push(x);
+    ip.push(y);
+}
+
+fn query_bounds<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let ((x0, y0), (x1, y1)) = ctx.turtle.bounds();
+
+    ip.push(x0);
+    ip.push(y0);
+    ip.push(x1);
+    ip.push(y1);
+}
+
+fn print_drawing<D: Dimension>(ip: &mut Ip<D>, ctx: &mut Context<D>) {
+    let svg = ctx.turtle.to_svg();
+
+    if !ctx.config.write_drawing(&svg) {
+        ip.reflect();
+    }
+}
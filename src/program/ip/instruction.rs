@@ -16,35 +16,61 @@
 // along with mycon.  If not, see <https://www.gnu.org/licenses/>.
 
 use chrono::{Utc, Datelike, Timelike};
-use rand;
 
-use crate::data::{Value, Point, Delta};
-use crate::program::Context;
+use crate::data::{Dimension, Value, Point, Delta, to_char};
+use crate::data::cell::Operation;
+use crate::data::space::Space;
+use crate::program::{Context, OverflowError};
 use super::Ip;
+use super::fingerprint::{self, Instruction};
 
-const HANDPRINT: i32 = 0x4a47_4d59;
+const HANDPRINT: Value = 0x4a47_4d59;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-impl Ip {
+impl<D: Dimension> Ip<D> {
     // Control flow
 
     pub(super) fn go_east(&mut self) {
-        self.set_delta(Delta { dx: 1, dy: 0 });
+        self.go(Delta { dx: 1, dy: 0, dz: 0 });
     }
 
     pub(super) fn go_south(&mut self) {
-        self.set_delta(Delta { dx: 0, dy: 1 });
+        self.go(Delta { dx: 0, dy: 1, dz: 0 });
     }
 
     pub(super) fn go_west(&mut self) {
-        self.set_delta(Delta { dx: -1, dy: 0 });
+        self.go(Delta { dx: -1, dy: 0, dz: 0 });
     }
 
     pub(super) fn go_north(&mut self) {
-        self.set_delta(Delta { dx: 0, dy: -1 });
+        self.go(Delta { dx: 0, dy: -1, dz: 0 });
     }
 
-    pub(super) fn trampoline(&mut self, ctx: &Context) {
+    pub(super) fn go_high(&mut self) {
+        self.go(Delta { dx: 0, dy: 0, dz: 1 });
+    }
+
+    pub(super) fn go_low(&mut self) {
+        self.go(Delta { dx: 0, dy: 0, dz: -1 });
+    }
+
+    /// Sets the delta, or adds to it in hover mode.
+    ///
+    /// The `Ip` is reflected instead if it would leave the dimensions of its
+    /// space, like moving north in Unefunge or high in Befunge.
+    fn go(&mut self, Delta { dx, dy, dz }: Delta) {
+        if (self.is_unefunge() && dy != 0) || (!self.is_trefunge() && dz != 0) {
+            self.reflect();
+        } else if self.hover {
+            let delta = self.delta;
+
+            self.set_delta(Delta { dx: delta.dx + dx, dy: delta.dy + dy, dz: delta.dz + dz });
+        } else {
+            self.set_delta(Delta { dx, dy, dz });
+        }
+    }
+
+    pub(super) fn trampoline(&mut self, ctx: &Context<D>) {
         if !ctx.space.is_last(self.position, self.delta) {
             self.step(&ctx.space);
         }
@@ -55,47 +81,58 @@ impl Ip {
     }
 
     pub(super) fn turn_left(&mut self) {
-        self.delta = self.delta.rotate_left();
+        if self.is_unefunge() {
+            self.reflect();
+        } else {
+            self.delta = self.delta.rotate_left();
+        }
     }
 
     pub(super) fn turn_right(&mut self) {
-        self.delta = self.delta.rotate_right();
+        if self.is_unefunge() {
+            self.reflect();
+        } else {
+            self.delta = self.delta.rotate_right();
+        }
     }
 
     pub(super) fn randomize_delta(&mut self) {
-        let (dx, dy) = match rand::random::<u8>() % 4 {
-            0 => ( 1,  0),
-            1 => ( 0,  1),
-            2 => (-1,  0),
-            3 => ( 0, -1),
+        let n = 2 * D::COUNT as u8;
+
+        let (dx, dy, dz) = match rand::random::<u8>() % n {
+            0 => ( 1,  0,  0),
+            1 => (-1,  0,  0),
+            2 => ( 0,  1,  0),
+            3 => ( 0, -1,  0),
+            4 => ( 0,  0,  1),
+            5 => ( 0,  0, -1),
             _ => unreachable!(),
         };
 
-        self.set_delta(Delta { dx, dy });
+        self.set_delta(Delta { dx, dy, dz });
     }
 
     pub(super) fn absolute_delta(&mut self) {
-        let dy = self.pop();
-        let dx = self.pop();
+        let delta = self.pop_delta();
 
-        self.set_delta(Delta { dx, dy });
+        self.set_delta(delta);
     }
 
-    pub(super) fn jump(&mut self, ctx: &Context) {
+    pub(super) fn jump(&mut self, ctx: &Context<D>) {
         let n = self.pop();
         let delta = self.delta;
 
-        self.delta *= n;
+        self.delta *= n as i32;
         self.step(&ctx.space);
 
         self.delta = delta;
     }
 
-    pub(super) fn stop(&mut self, ctx: &mut Context) {
+    pub(super) fn stop(&mut self, ctx: &mut Context<D>) {
         ctx.control.delete_ip();
     }
 
-    pub(super) fn terminate(&mut self, ctx: &mut Context) {
+    pub(super) fn terminate(&mut self, ctx: &mut Context<D>) {
         ctx.control.terminate(self.pop());
     }
 
@@ -112,8 +149,8 @@ impl Ip {
     }
 
     pub(super) fn greater_than(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
+        let b = self.pop_cell();
+        let a = self.pop_cell();
 
         if a > b {
             self.push(1);
@@ -142,9 +179,19 @@ impl Ip {
         }
     }
 
+    pub(super) fn if_high_low(&mut self) {
+        let v = self.pop();
+
+        if v == 0 {
+            self.go_low();
+        } else {
+            self.go_high();
+        }
+    }
+
     pub(super) fn compare(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
+        let b = self.pop_cell();
+        let a = self.pop_cell();
 
         if a < b {
             self.turn_left();
@@ -160,18 +207,18 @@ impl Ip {
     }
 
     pub(super) fn duplicate(&mut self) {
-        let v = self.pop();
+        let v = self.pop_cell();
 
-        self.push(v);
-        self.push(v);
+        self.push_cell(v.clone());
+        self.push_cell(v);
     }
 
     pub(super) fn swap(&mut self) {
-        let v = self.pop();
-        let w = self.pop();
+        let v = self.pop_cell();
+        let w = self.pop_cell();
 
-        self.push(v);
-        self.push(w);
+        self.push_cell(v);
+        self.push_cell(w);
     }
 
     pub(super) fn clear(&mut self) {
@@ -183,7 +230,7 @@ impl Ip {
     pub(super) fn begin_block(&mut self) {
         let n = self.pop();
 
-        self.stacks.create_stack(n, self.storage);
+        self.stacks.create_stack::<D>(n, self.storage);
         self.storage = self.position + self.delta;
     }
 
@@ -194,7 +241,7 @@ impl Ip {
         }
 
         let n = self.pop();
-        let storage = self.stacks.delete_stack(n);
+        let storage = self.stacks.delete_stack::<D>(n);
 
         self.storage = storage;
     }
@@ -276,46 +323,37 @@ impl Ip {
         self.push(15);
     }
 
-    pub(super) fn add(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
-
-        self.push(a + b);
+    pub(super) fn add(&mut self, ctx: &mut Context<D>) {
+        self.arithmetic(ctx, Operation::Add, '+');
     }
 
-    pub(super) fn sub(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
-
-        self.push(a - b);
+    pub(super) fn sub(&mut self, ctx: &mut Context<D>) {
+        self.arithmetic(ctx, Operation::Sub, '-');
     }
 
-    pub(super) fn mul(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
-
-        self.push(a * b);
+    pub(super) fn mul(&mut self, ctx: &mut Context<D>) {
+        self.arithmetic(ctx, Operation::Mul, '*');
     }
 
-    pub(super) fn div(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
+    pub(super) fn div(&mut self, ctx: &mut Context<D>) {
+        self.arithmetic(ctx, Operation::Div, '/');
+    }
 
-        if b == 0 {
-            self.push(0)
-        } else {
-            self.push(a / b);
-        }
+    pub(super) fn rem(&mut self, ctx: &mut Context<D>) {
+        self.arithmetic(ctx, Operation::Rem, '%');
     }
 
-    pub(super) fn rem(&mut self) {
-        let b = self.pop();
-        let a = self.pop();
+    /// Pops two cells and pushes the result of the operation on them.
+    ///
+    /// Division and remainder by zero result in zero. If the result overflows
+    /// and overflow is trapped, the program is stopped instead.
+    fn arithmetic(&mut self, ctx: &mut Context<D>, op: Operation, command: char) {
+        let b = self.pop_cell();
+        let a = self.pop_cell();
 
-        if b == 0 {
-            self.push(0);
-        } else {
-            self.push(a % b);
+        match op.apply(&a, &b, self.cell_size, ctx.config.get_overflow()) {
+            Some(v) => self.push_cell(v),
+            None    => ctx.control.trap(OverflowError::new(self.id, command, self.position)),
         }
     }
 
@@ -325,7 +363,7 @@ impl Ip {
         self.string = true;
     }
 
-    pub(super) fn fetch_char(&mut self, ctx: &Context) {
+    pub(super) fn fetch_char(&mut self, ctx: &Context<D>) {
         let v = if ctx.space.is_last(self.position, self.delta) {
             32
         } else {
@@ -336,45 +374,43 @@ impl Ip {
         self.step(&ctx.space);
     }
 
-    pub(super) fn store_char(&mut self, ctx: &mut Context) {
-        let v = self.pop();
+    pub(super) fn store_char(&mut self, ctx: &mut Context<D>) {
+        let v = self.pop_cell();
 
-        ctx.space.set(self.position + self.delta, v);
+        ctx.space.set_cell(self.position + self.delta, v);
         self.step(&ctx.space);
     }
 
     // Reflection
 
-    pub(super) fn get(&mut self, ctx: &Context) {
-        let dy = self.pop();
-        let dx = self.pop();
+    pub(super) fn get(&mut self, ctx: &Context<D>) {
+        let position = self.pop_position();
 
-        let v = ctx.space.get(self.storage + Delta { dx, dy });
-        self.push(v);
+        let v = ctx.space.get_cell(position);
+        self.push_cell(v);
     }
 
-    pub(super) fn put(&mut self, ctx: &mut Context) {
-        let dy = self.pop();
-        let dx = self.pop();
-        let v = self.pop();
+    pub(super) fn put(&mut self, ctx: &mut Context<D>) {
+        let position = self.pop_position();
+        let v = self.pop_cell();
 
-        ctx.space.set(self.storage + Delta { dx, dy }, v);
+        ctx.space.set_cell(position, v);
     }
 
     // Input/Output
 
-    pub(super) fn output_decimal(&mut self, ctx: &mut Context) {
-        let v = self.pop();
+    pub(super) fn output_decimal(&mut self, ctx: &mut Context<D>) {
+        let v = self.pop_cell();
 
-        if !ctx.config.write_decimal(v) {
+        if !ctx.config.write_decimal(&v) {
             self.reflect();
         }
     }
 
-    pub(super) fn output_char(&mut self, ctx: &mut Context) {
+    pub(super) fn output_char(&mut self, ctx: &mut Context<D>) {
         let v = self.pop();
 
-        if let Some(c) = std::char::from_u32(v as u32) {
+        if let Some(c) = to_char(v) {
             if !ctx.config.write_char(c) {
                 self.reflect();
             }
@@ -383,86 +419,40 @@ impl Ip {
         }
     }
 
-    pub(super) fn input_decimal(&mut self, ctx: &mut Context) {
+    pub(super) fn input_decimal(&mut self, ctx: &mut Context<D>) {
         match ctx.config.read_decimal() {
-            Some(v) => self.push(v),
+            Some(v) => self.push_cell(v),
             None    => self.reflect(),
         }
     }
 
-    pub(super) fn input_char(&mut self, ctx: &mut Context) {
+    pub(super) fn input_char(&mut self, ctx: &mut Context<D>) {
         match ctx.config.read_char() {
-            Some(v) => self.push(v as i32),
+            Some(v) => self.push(v as Value),
             None    => self.reflect(),
         }
     }
 
-    pub(super) fn write_file(&mut self, ctx: &mut Context) {
+    pub(super) fn write_file(&mut self, ctx: &mut Context<D>) {
         if let Some(path) = self.pop_string() {
             let v = self.pop();
-            let y = self.pop();
-            let x = self.pop();
-            let h = self.pop();
-            let w = self.pop();
+            let min = self.pop_position();
+            let Delta { dx: w, dy: h, dz: d } = self.pop_size();
 
             let trim_right = v & 1 == 1;
-
-            let mut i;
-            let mut j = y;
-            let mut s = String::new();
-            let mut spaces = 0;
-            let mut newlines = 0;
-
-            while j - y < h {
-                i = x;
-
-                while i - x < w {
-                    let Point { x: sx, y: sy } = self.storage;
-                    let v = ctx.space.get(Point { x: i + sx, y: j + sy });
-
-                    if v == ' ' as i32 {
-                        spaces += 1;
-                    } else {
-                        for _ in 0..spaces {
-                            s.push(' ');
-                        }
-
-                        spaces = 0;
-
-                        if i == x {
-                            for _ in 0..newlines {
-                                s.push('\n');
-                            }
-                            newlines = 0;
-                        }
-
-                        if let Some(c) = std::char::from_u32(v as u32) {
-                            s.push(c);
-                        } else {
-                            self.reflect();
-                            return;
-                        }
-                    }
-
-                    i += 1;
-                }
-
-                if !trim_right {
-                    for _ in 0..spaces {
-                        s.push(' ');
-                    }
+            let mut planes = Vec::new();
+
+            for k in 0..d {
+                match plane_text(&ctx.space, Point { z: min.z + k, ..min }, w, h, trim_right) {
+                    Some(plane) => planes.push(plane),
+                    None        => {
+                        self.reflect();
+                        return;
+                    },
                 }
-
-                j += 1;
-                newlines += 1;
-                spaces = 0;
             }
 
-            if !trim_right {
-                for _ in 1..newlines {
-                    s.push('\n');
-                }
-            }
+            let mut s = planes.join("\x0c");
 
             s.push('\n');
 
@@ -474,40 +464,48 @@ impl Ip {
         }
     }
 
-    pub(super) fn read_file(&mut self, ctx: &mut Context) {
+    pub(super) fn read_file(&mut self, ctx: &mut Context<D>) {
         if let Some(path) = self.pop_string() {
             let v = self.pop();
-            let y = self.pop();
-            let x = self.pop();
+            let Delta { dx: x, dy: y, dz: z } = self.pop_delta();
 
-            let linear = v & 1 == 1;
+            let linear = v & 1 == 1 || self.is_unefunge();
+            let planes = !linear && self.is_trefunge();
 
             let mut i = x;
             let mut j = y;
+            let mut k = z;
 
             let mut w = 0;
+            let mut h = 0;
 
             if let Some(s) = ctx.config.read_file(&path) {
                 for c in s.chars() {
                     if c == '\n' && !linear {
                         i = x;
                         j += 1;
+                    } else if c == '\x0c' && planes {
+                        i = x;
+                        j = y;
+                        k += 1;
                     } else if linear || c != '\r' {
                         if c != ' ' {
-                            let Point { x: sx, y: sy } = self.storage;
-                            ctx.space.set(Point { x: i + sx, y: j + sy }, c as i32);
+                            let p = self.storage + Delta { dx: i, dy: j, dz: k };
+                            ctx.space.set(p, c as Value);
                         }
                         i += 1;
                         if i - x > w {
                             w = i - x;
                         }
                     }
+
+                    if j - y > h {
+                        h = j - y;
+                    }
                 }
 
-                self.push(w);
-                self.push(j - y);
-                self.push(x);
-                self.push(y);
+                self.push_delta(Delta { dx: w, dy: h, dz: k - z + 1 });
+                self.push_delta(Delta { dx: x, dy: y, dz: z });
             } else {
                 self.reflect();
             }
@@ -518,7 +516,7 @@ impl Ip {
 
     // Concurrency
 
-    pub(super) fn split(&mut self, ctx: &mut Context) {
+    pub(super) fn split(&mut self, ctx: &mut Context<D>) {
         let mut ip = self.clone();
 
         ip.reflect();
@@ -527,49 +525,56 @@ impl Ip {
 
     // Fingerprints
 
-    pub(super) fn load_semantics(&mut self) {
-        let v = self.pop();
-
-        if v <= 0 {
-            self.reflect();
-        } else {
-            #[allow(unused)]
-            let mut fp = 0;
-
-            for _ in 0..v {
-                let n = self.pop();
+    pub(super) fn load_semantics(&mut self, ctx: &mut Context<D>) {
+        let id = self.pop_fingerprint();
 
-                fp <<= 8;
-                fp += n;
-            }
+        match id.and_then(|id| fingerprint::find(ctx, id).map(|fp| (id, fp))) {
+            Some((id, fp)) => {
+                self.semantics.load(&fp);
+                self.push(id);
+                self.push(1);
+            },
+            None           => self.reflect(),
+        }
+    }
 
-            self.reflect(); // TODO implement
+    pub(super) fn unload_semantics(&mut self, ctx: &mut Context<D>) {
+        match self.pop_fingerprint().and_then(|id| fingerprint::find(ctx, id)) {
+            Some(fp) => self.semantics.unload(&fp),
+            None     => self.reflect(),
         }
     }
 
-    pub(super) fn unload_semantics(&mut self) {
-        let v = self.pop();
+    pub(super) fn fingerprint_instruction(&mut self, ctx: &mut Context<D>, c: char) {
+        match self.semantics.get(c) {
+            Some(Instruction::Builtin(f)) => f(self, ctx),
+            Some(Instruction::Custom(id)) => self.execute_custom(ctx, id, c),
+            None                          => self.reflect(),
+        }
+    }
 
-        if v <= 0 {
-            self.reflect();
-        } else {
-            #[allow(unused)]
-            let mut fp = 0;
+    /// Pops a fingerprint ID off the stack, as used by `(` and `)`.
+    ///
+    /// Returns `None` if the number of cells making up the ID is not positive.
+    fn pop_fingerprint(&mut self) -> Option<Value> {
+        let n = self.pop();
 
-            for _ in 0..v {
-                let n = self.pop();
+        if n <= 0 {
+            return None;
+        }
 
-                fp <<= 8;
-                fp += n;
-            }
+        let mut fp: Value = 0;
 
-            self.reflect(); // TODO implement
+        for _ in 0..n {
+            fp = fp.wrapping_shl(8).wrapping_add(self.pop());
         }
+
+        Some(fp)
     }
 
     // Other
 
-    pub(super) fn iterate(&mut self, ctx: &mut Context) {
+    pub(super) fn iterate(&mut self, ctx: &mut Context<D>) {
         let n = self.pop();
 
         if n <= 0 {
@@ -579,20 +584,20 @@ impl Ip {
             return;
         }
 
-        let v = self.peek_command(&ctx.space);
-        if let Some(c) = std::char::from_u32(v as u32) {
+        let (position, v) = self.peek_command(&ctx.space);
+        if let Some(c) = to_char(v) {
             if !is_idempotent(c) {
                 for _ in 1..n {
-                    self.execute(ctx, c);
+                    self.execute(ctx, c, position);
                 }
             }
-            self.execute(ctx, c);
+            self.execute(ctx, c, position);
         } else {
             self.reflect();
         }
     }
 
-    pub(super) fn system_execute(&mut self, ctx: &mut Context) {
+    pub(super) fn system_execute(&mut self, ctx: &mut Context<D>) {
         if let Some(cmd) = self.pop_string() {
             match ctx.config.execute(&cmd) {
                 Some(v) => self.push(v),
@@ -603,12 +608,12 @@ impl Ip {
         }
     }
 
-    pub(super) fn get_sysinfo(&mut self, ctx: &mut Context) {
+    pub(super) fn get_sysinfo(&mut self, ctx: &mut Context<D>) {
         let n = self.pop();
         let mut num_cells = 0;
 
         let space = &ctx.space;
-        let io = &ctx.config;
+        let io = &mut ctx.config;
 
         let sizes = self.stacks.stack_sizes();
 
@@ -630,56 +635,50 @@ impl Ip {
         // Size of each stack
         num_cells += sizes.len();
         for &l in sizes.iter() {
-            self.push(l as i32);
+            self.push(l as Value);
         }
 
         // Total number of stacks
         num_cells += 1;
-        self.push(sizes.len() as i32);
+        self.push(sizes.len() as Value);
 
         let dt = Utc::now();
 
         // Time
         num_cells += 1;
-        self.push(((dt.hour() << 16) + (dt.minute() << 8) + dt.second()) as i32);
+        self.push(Value::from((dt.hour() << 16) + (dt.minute() << 8) + dt.second()));
 
         // Date
         num_cells += 1;
-        self.push(((dt.year() - 1900) << 16) + ((dt.month() << 8) + dt.day()) as i32);
+        self.push(Value::from(((dt.year() - 1900) << 16) + ((dt.month() << 8) + dt.day()) as i32));
 
-        let (x0, y0) = space.min();
-        let (x1, y1) = space.max();
+        let min = space.min();
+        let max = space.max();
+        let dims = D::COUNT;
 
         // Program size
-        num_cells += 2;
-        self.push(x1 - x0);
-        self.push(y1 - y0);
+        num_cells += dims;
+        self.push_delta(Delta { dx: max.x - min.x, dy: max.y - min.y, dz: max.z - min.z });
 
         // Program start
-        num_cells += 2;
-        self.push(x0);
-        self.push(y0);
+        num_cells += dims;
+        self.push_delta(Delta { dx: min.x, dy: min.y, dz: min.z });
 
-        let Point { x, y } = self.storage;
+        let Point { x, y, z } = self.storage;
 
         // Storage offset
-        num_cells += 2;
-        self.push(x);
-        self.push(y);
-
-        let Delta { dx, dy } = self.delta;
+        num_cells += dims;
+        self.push_delta(Delta { dx: x, dy: y, dz: z });
 
         // Delta
-        num_cells += 2;
-        self.push(dx);
-        self.push(dy);
+        num_cells += dims;
+        self.push_delta(self.delta);
 
-        let Point { x, y } = self.position;
+        let Point { x, y, z } = self.position;
 
         // Position
-        num_cells += 2;
-        self.push(x);
-        self.push(y);
+        num_cells += dims;
+        self.push_delta(Delta { dx: x, dy: y, dz: z });
 
         // Team number
         num_cells += 1;
@@ -692,11 +691,11 @@ impl Ip {
 
         // Dimension
         num_cells += 1;
-        self.push(2);
+        self.push(dims as Value);
 
         // Path separator
         num_cells += 1;
-        self.push('/' as i32);
+        self.push('/' as Value);
 
         // Operating paradigm
         num_cells += 1;
@@ -712,7 +711,7 @@ impl Ip {
 
         // Cell size
         num_cells += 1;
-        self.push(4);
+        self.push(self.cell_size.bytes());
 
         // Flags
         num_cells += 1;
@@ -722,23 +721,77 @@ impl Ip {
             let v = self.stacks.nth(n as usize);
 
             self.stacks.delete_cells(num_cells);
-            self.push(v);
+            self.push_cell(v);
         }
     }
 }
 
-fn is_idempotent(c: char) -> bool {
-    match c {
-        '<' | '>' | '?' | '@' | '^' | 'n' | 'q' | 'v' | 'z' => true,
-        _                                                   => false,
+/// Renders the rectangle of the given size in the plane of `min` as text, as
+/// written by the `o` instruction.
+///
+/// Returns `None` if the rectangle contains a cell that is not a valid
+/// character.
+fn plane_text<D: Dimension>(
+    space: &Space<D>,
+    min: Point,
+    w: i32,
+    h: i32,
+    trim_right: bool,
+) -> Option<String> {
+    let mut s = String::new();
+    let mut spaces = 0;
+    let mut newlines = 0;
+
+    for j in 0..h.max(0) {
+        for i in 0..w.max(0) {
+            let v = space.get(min + Delta { dx: i, dy: j, dz: 0 });
+
+            if v == ' ' as Value {
+                spaces += 1;
+            } else {
+                for _ in 0..newlines {
+                    s.push('\n');
+                }
+
+                for _ in 0..spaces {
+                    s.push(' ');
+                }
+
+                newlines = 0;
+                spaces = 0;
+
+                s.push(to_char(v)?);
+            }
+        }
+
+        if !trim_right {
+            for _ in 0..spaces {
+                s.push(' ');
+            }
+        }
+
+        newlines += 1;
+        spaces = 0;
     }
+
+    if !trim_right {
+        for _ in 1..newlines {
+            s.push('\n');
+        }
+    }
+
+    Some(s)
+}
+
+fn is_idempotent(c: char) -> bool {
+    matches!(c, '<' | '>' | '?' | '@' | '^' | 'n' | 'q' | 'v' | 'z')
 }
 
 fn version_number(s: &str) -> Value {
     let mut r = 0;
 
     for p in s.split('.') {
-        let n: i32 = p.parse().unwrap();
+        let n: Value = p.parse().unwrap();
 
         r <<= 8;
         r += n;
@@ -0,0 +1,147 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+extern crate mycon;
+
+mod util;
+
+use std::io::{self, Cursor};
+
+use mycon::{CellSize, Config, Overflow, Point, Program};
+
+use self::util::{test_output, test_output_with};
+
+#[test]
+fn sysinfo_cell_size() {
+    let code = "2y.@";
+
+    test_output(code, "4 ");
+    test_output_with(code, "8 ", |config| config.cell_size(CellSize::Bits64));
+    test_output_with(code, "0 ", |config| config.cell_size(CellSize::Unbounded));
+}
+
+#[test]
+fn bits64() {
+    let code = "88*:*:*:*.@";
+
+    test_output_with(code, "281474976710656 ", |config| config.cell_size(CellSize::Bits64));
+}
+
+#[test]
+fn bits64_exit_status() {
+    let code = "88*:*:*88*4**3+q";
+    let config = Config::new().input(io::empty()).cell_size(CellSize::Bits64);
+
+    assert_eq!(3, Program::read(code).config(config).run());
+}
+
+#[test]
+fn bits64_toys_shift() {
+    let code = "\"SYOT\"4($$188*:*44**:*1+H.08-088*:*44**:*1+-H.@";
+
+    test_output_with(code, "0 -1 ", |config| config.cell_size(CellSize::Bits64));
+}
+
+#[test]
+fn bits64_toys_matrix() {
+    for c in &["F", "G"] {
+        let code = format!("\"SYOT\"4($$#@.988*:*44**:*1+100{}1.@", c);
+
+        test_output_with(&code, "0 9 ", |config| config.cell_size(CellSize::Bits64));
+    }
+}
+
+#[test]
+fn unbounded() {
+    let code = "88*:*:*:*:*:.88*:*:*:*/.@";
+
+    let output = "79228162514264337593543950336 281474976710656 ";
+    test_output_with(code, output, |config| config.cell_size(CellSize::Unbounded));
+}
+
+#[test]
+fn unbounded_position() {
+    let code = "'A88*:*:*:*88*:**8*2*5+0p50g.@";
+
+    test_output_with(code, "65 ", |config| config.cell_size(CellSize::Unbounded));
+}
+
+#[test]
+fn unbounded_space() {
+    let code = "088*:*:*:*:*-00p00g.@";
+
+    test_output_with(code, "-79228162514264337593543950336 ", |config| {
+        config.cell_size(CellSize::Unbounded)
+    });
+}
+
+#[test]
+fn overflow_wrap() {
+    test_output("88*:*:*88*2**.@", "-2147483648 ");
+    test_output("088*:*:*88**:1-+-1-01-/.@", "-2147483648 ");
+    test_output("088*:*:*88**:1-+-1-01-%.@", "0 ");
+    test_output_with("88*:*:*:*88*:**8*.@", "-9223372036854775808 ", |config| {
+        config.cell_size(CellSize::Bits64)
+    });
+}
+
+#[test]
+fn overflow_saturate() {
+    fn saturate(config: Config) -> Config {
+        config.overflow(Overflow::Saturate)
+    }
+
+    test_output_with("88*:*:*88*2**.@", "2147483647 ", saturate);
+    test_output_with("088*:*:*88**:1-+-1-.@", "-2147483648 ", saturate);
+    test_output_with("088*:*:*88**:1-+-1-01-/.@", "2147483647 ", saturate);
+    test_output_with("088*:*:*88**:1-+-1-01-%.@", "0 ", saturate);
+}
+
+#[test]
+fn overflow_trap() {
+    let code = "1.88*:*:*88*2**.@";
+    let mut output = Vec::new();
+
+    let (exit, error) = {
+        let config = Config::new()
+            .input(io::empty())
+            .output(&mut output)
+            .overflow(Overflow::Trap);
+        let mut prog = Program::read(code).config(config);
+
+        (prog.run(), prog.error().cloned())
+    };
+
+    let error = error.unwrap();
+
+    assert_eq!(1, exit);
+    assert_eq!(0, error.id());
+    assert_eq!('*', error.command());
+    assert_eq!(Point { x: 14, y: 0, z: 0 }, error.position());
+    assert_eq!(&b"1 "[..], &*output);
+}
+
+#[test]
+fn overflow_trap_input() {
+    let code = "&.@";
+    let input = "9999999999\n";
+
+    test_output_with(code, "1410065407 ", |config| config.input(Cursor::new(input)));
+    test_output_with(code, "", |config| {
+        config.input(Cursor::new(input)).overflow(Overflow::Trap)
+    });
+}
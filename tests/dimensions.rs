@@ -0,0 +1,109 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+extern crate mycon;
+
+mod util;
+
+use std::fs;
+
+use mycon::{Befunge, Trefunge, Unefunge};
+
+use self::util::{TempDir, test_output_in};
+
+#[test]
+fn unefunge() {
+    for c in &["^", "v", "|", "[", "]", "01w"] {
+        let code = format!("0{}@.1", c);
+
+        test_output_in(&code, Unefunge, "1 ");
+    }
+
+    for c in &["|", "w", "m"] {
+        let code = format!("#@..7{}", c);
+
+        test_output_in(&code, Unefunge, "0 0 7 7 ");
+    }
+
+    test_output_in("'A9p9g,@", Unefunge, "A");
+}
+
+#[test]
+fn unefunge_sysinfo() {
+    let code = "7y.by.@";
+
+    test_output_in(code, Unefunge, "1 1 ");
+}
+
+#[test]
+fn unefunge_toys() {
+    let code = "\"SYOT\"4($$'x3aa*Saa*g,aa*2+g,aa*2aa*a+Maa*a+g,aa*b+g,aa*g.@";
+
+    test_output_in(code, Unefunge, "xxxx32 ");
+
+    test_output_in("\"SYOT\"4($$#@....571T", Unefunge, "0 0 0 0 5 7 1 7 ");
+}
+
+#[test]
+fn befunge() {
+    test_output_in("#@..7m", Befunge, "0 0 7 7 ");
+}
+
+#[test]
+fn trefunge() {
+    test_output_in("1m\x0c >2.@", Trefunge, "2 ");
+    test_output_in("l\x0c\x0c>3.@", Trefunge, "3 ");
+    test_output_in("'A123p123g,@", Trefunge, "A");
+}
+
+#[test]
+fn trefunge_toys() {
+    let code = "\"SYOT\"4($$'x212050S151g,052g.050212070C171g,@";
+    test_output_in(code, Trefunge, "x32 x");
+
+    test_output_in("\"SYOT\"4($$Z\x0c          @3.@", Trefunge, "3 ");
+    test_output_in("\"SYOT\"4($$712T@\x0c             >.@", Trefunge, "7 ");
+}
+
+#[test]
+fn trefunge_indv() {
+    let code = "\"VDNI\"4($$0010p1110p4210p567010W010V...@";
+
+    test_output_in(code, Trefunge, "7 6 5 ");
+}
+
+#[test]
+fn trefunge_sysinfo() {
+    let code = "7y.cy.@";
+
+    test_output_in(code, Trefunge, "3 4 ");
+}
+
+#[test]
+fn trefunge_file() {
+    let temp = TempDir::new("trefunge_file");
+    let path = temp.path().join("out");
+    let name: String = path.to_str().unwrap().chars().rev().collect();
+
+    let code = format!("2120101 0\"{}\"o@\nab\n\x0c\ncd", name);
+    test_output_in(&code, Trefunge, "");
+
+    assert_eq!("ab\x0ccd\n", fs::read_to_string(&path).unwrap());
+
+    let code = format!("5000 0\"{}\"i501g,500g,@", name);
+    test_output_in(&code, Trefunge, "ca");
+}
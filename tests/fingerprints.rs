@@ -0,0 +1,606 @@
+// Copyright 2018 Johannes M. Griebler
+//
+// This file is part of mycon.
+//
+// mycon is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// mycon is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with mycon.  If not, see <https://www.gnu.org/licenses/>.
+
+extern crate mycon;
+
+mod util;
+
+use std::io::{self, Cursor, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+
+use chrono::{Datelike, Utc};
+
+use mycon::{CellSize, Config, FileView, NetworkView, Fingerprint, Handle, Program};
+use mycon::{Delta, Point, Trefunge};
+
+use self::util::{TempDir, test_output, test_output_with};
+
+const TEST: i64 = 0x5445_5354;
+
+fn reversed(path: &Path) -> String {
+    path.to_str().unwrap().chars().rev().collect()
+}
+
+struct Counter(i64);
+
+impl Fingerprint for Counter {
+    fn instructions(&self) -> &str {
+        "IR"
+    }
+
+    fn execute(&mut self, instruction: char, handle: &mut Handle) -> bool {
+        match instruction {
+            'I' => {
+                self.0 += 1;
+                handle.push(self.0);
+                true
+            },
+            _   => false,
+        }
+    }
+}
+
+/// Goes high with `H`, pushes the z coordinate of the IP and the value below
+/// it with `P`.
+struct Lift;
+
+impl Fingerprint for Lift {
+    fn instructions(&self) -> &str {
+        "HP"
+    }
+
+    fn execute(&mut self, instruction: char, handle: &mut Handle) -> bool {
+        match instruction {
+            'H' => handle.set_delta(Delta { dx: 0, dy: 0, dz: 1 }),
+            'P' => {
+                let Point { x, y, z } = handle.position();
+
+                handle.push(i64::from(z));
+                handle.push(handle.get(Point { x, y, z: z - 1 }));
+            },
+            _   => return false,
+        }
+
+        true
+    }
+}
+
+#[test]
+fn load_unknown() {
+    let code = "\"KNUJ\"4#v(0.@\n        >1.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn unload_unknown() {
+    let code = "\"KNUJ\"4#v)0.@\n        >1.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn unbound_instruction() {
+    let code = "#vA0.@\n >1.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn custom_execute() {
+    let code = "\"TSET\"4(.II+.@";
+
+    test_output_with(code, "1 3 ", |config| config.fingerprint(TEST, Counter(0)));
+}
+
+#[test]
+fn custom_trefunge() {
+    let code = "\"TSET\"4($$Hx\x0c          >P,.@";
+    let mut output = Vec::new();
+
+    {
+        let config = Config::new()
+            .input(io::empty())
+            .output(&mut output)
+            .fingerprint(TEST, Lift);
+
+        Program::read_in(code, Trefunge).config(config).run();
+    }
+
+    assert_eq!(&b"x1 "[..], &*output);
+}
+
+#[test]
+fn custom_reflect() {
+    let code = "\"TSET\"4($$#vR0.@\n           >1.@";
+
+    test_output_with(code, "1 ", |config| config.fingerprint(TEST, Counter(0)));
+}
+
+#[test]
+fn null() {
+    let code = "\"LLUN\"4($$#vA0.@\n           >1.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn null_unload() {
+    let code = "\"AMOR\"4($$\"LLUN\"4($$\"LLUN\"4)I.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn roma() {
+    let code = "\"AMOR\"4($$MMC-+.LX-.VI+.D.@";
+
+    test_output(code, "1900 40 6 500 ");
+}
+
+#[test]
+fn modu() {
+    let code = "\"UDOM\"4($$07-3M.07-3U.07-3R.703-M.50R.@";
+
+    test_output(code, "2 1 -1 -2 0 ");
+}
+
+#[test]
+fn bool() {
+    let code = "\"LOOB\"4($$65A.65O.65X.0N.@";
+
+    test_output(code, "4 7 3 -1 ");
+}
+
+#[test]
+fn fpsp() {
+    let code = "\"PSPF\"4($$3F2FDP2FQP5F2FDI.0\"5.2-\"RP@";
+
+    test_output(code, "1.500000 1.414214 2 -2.500000 ");
+}
+
+#[test]
+fn fpdp() {
+    let code = "\"PDPF\"4($$3F2FDP2FQP5F2FDI.0\"5.2-\"RP1FXP@";
+
+    test_output(code, "1.500000 1.414214 2 -2.500000 2.718282 ");
+}
+
+#[test]
+fn fpdp_wrap() {
+    let code = "\"PDPF\"4($$aa*:*:*Faa*FMI.@";
+
+    test_output(code, "1410065408 ");
+}
+
+#[test]
+fn fpdp_big_cell() {
+    let code = "\"PDPF\"4($$aa*:*:*aa**FI.@";
+
+    test_output_with(code, "10000000000 ", |config| config.cell_size(CellSize::Bits64));
+}
+
+#[test]
+fn fixp() {
+    let code = "\"PXIF\"4($$aa*:*f2**I.aa*:*2/J.aa*Q.25R.05-S.aa*P.1D.@";
+
+    test_output(code, "5000 300000 10 32 -1 314 0 ");
+}
+
+#[test]
+fn fixp_big_cell() {
+    let code = "\"PXIF\"4($$aa*:*:*aa**:N:.V.:Q.P.@";
+
+    test_output_with(code, "-10000000000 10000000000 100000 31415926536 ", |config| {
+        config.cell_size(CellSize::Bits64)
+    });
+}
+
+#[test]
+fn strn() {
+    let code = "\"NRTS\"4($$0\"dlrow\"0\" ,olleh\"AD0\"cba\"N.D0\"edcba\"2LD0\"edcba\"2RD0\"edcba\"12MD0\"c\"0\"dcba\"FD0\"54-\"V.aa*5-SD0\"b\"0\"a\"C.0\"ih\"55P55GD@";
+
+    test_output(code, "hello, world3 abcabdebccd-45 951 hi");
+}
+
+#[test]
+fn file() {
+    let dir = TempDir::new("file");
+    let path = reversed(&dir.path().join("test.txt"));
+    let code = format!(
+        "\"ELIF\"4($$0110\"{0}\"O5WC0100\"{0}\"OG.,,,,,$C0\"{0}\"D@\nhello",
+        path,
+    );
+
+    test_output(&code, "5 hello");
+}
+
+#[test]
+fn file_write_large() {
+    let dir = TempDir::new("file_write_large");
+    let path = reversed(&dir.path().join("test.txt"));
+    let code = format!("\"ELIF\"4($$0110\"{}\"Oaa*a*5*WL.C@", path);
+
+    test_output(&code, "5000 ");
+}
+
+#[test]
+fn file_deny() {
+    let code = "\"ELIF\"4($$000\"a\"#vO0.@\n                 >1.@";
+
+    test_output_with(code, "1 ", |config| config.file_view(FileView::Deny));
+}
+
+#[test]
+fn dirf() {
+    let temp = TempDir::new("dirf");
+    let dir = temp.path();
+    let code = "\"FRID\"4($$0\"a\"M0\"a\"C0\"b\"M0\"c\"M0\"c\"R@";
+
+    test_output_with(code, "", |config| config.working_dir(dir));
+
+    assert!(dir.join("a").join("b").is_dir());
+    assert!(!dir.join("a").join("c").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn dirf_execute() {
+    let temp = TempDir::new("dirf_execute");
+    let dir = temp.path();
+    let code = "\"FRID\"4($$0\"a\"M0\"a\"C0\"tuo hcuot\"=.@";
+
+    test_output_with(code, "0 ", |config| config.working_dir(dir));
+
+    assert!(dir.join("a").join("out").is_file());
+}
+
+#[test]
+fn dirf_root_dir() {
+    let temp = TempDir::new("dirf_root_dir");
+    let dir = temp.path();
+    let outside = dir.parent().unwrap();
+
+    for path in &[Path::new(".."), Path::new("a/../.."), outside] {
+        let code = format!("\"FRID\"4($$#@0\"{}\"C1.@", reversed(path));
+
+        test_output_with(&code, "", |config| config.working_dir(dir).root_dir(dir));
+    }
+
+    let code = "\"FRID\"4($$0\"a\"M0\"a\"C#@0\"..\"C1.@";
+
+    test_output_with(code, "1 ", |config| config.working_dir(dir).root_dir(dir));
+}
+
+#[test]
+fn hrti() {
+    let code = "\"ITRH\"4($$G.#vT0.@\n             >MT$E#vT0.@\n                   >1.@";
+
+    test_output(code, "1 1 ");
+}
+
+#[test]
+fn refc() {
+    let code = "\"CFER\"4($$12R34R12R.D..D..@";
+
+    test_output(code, "1 4 3 2 1 ");
+}
+
+#[test]
+fn toys() {
+    let code = "\"SYOT\"4($$73A...52B..5D.5I.5N.18H.f01-H.123E.234P.X1.@";
+
+    test_output(code, "7 7 7 3 7 4 6 -5 256 7 6 24 0 ");
+}
+
+#[test]
+fn toys_copy() {
+    let code = "\"SYOT\"4($$013141C41g,51g,61g,413151K41g,51g,61g,71g,013102M01g.02g.@\nabc";
+
+    test_output(code, "abcaabc32 97 ");
+}
+
+#[test]
+fn toys_matrix() {
+    let code = "\"SYOT\"4($$12342201F2201G....72103S03g.13g.@";
+
+    test_output(code, "4 3 2 1 7 7 ");
+}
+
+#[test]
+fn toys_large_block() {
+    let code = "\"SYOT\"4($$7aa*a*5*305S05aa*a*5*3a9Maa*a*5*9+bg.aa*a*5*1-7g.99g.@";
+
+    test_output(code, "7 32 32 ");
+}
+
+#[test]
+fn toys_translate() {
+    let code = "\"SYOT\"4($$2Jb0g.b2g.b3g.@\n           x";
+
+    test_output(code, "32 74 120 ");
+}
+
+#[test]
+fn orth() {
+    let code = "\"HTRO\"4($$65A.65O.65E.01G,'a21P21G,0\"ih\"S0Z1.1Z2.@";
+
+    test_output(code, "4 7 3 Hahi0 2 ");
+}
+
+#[test]
+fn mode() {
+    let code = "\"EDOM\"4($$I123I...123Q...QH>919.9@";
+
+    test_output(code, "1 2 3 1 2 3 1 ");
+}
+
+#[test]
+fn mode_switch() {
+    let code = "\"EDOM\"4($$S0{0}Sc0g,e0g,@";
+
+    test_output(code, "}{");
+}
+
+#[test]
+fn mode_switch_iterate() {
+    let code = "\"EDOM\"4($$S1k[\n   @,g0d,g0c<";
+
+    test_output(code, "k]");
+}
+
+#[test]
+fn indv() {
+    let code = "\"VDNI\"4($$101p311p01G,'z01P01G,01V..5701W01V..@\n   c";
+
+    test_output(code, "cz122 32 7 5 ");
+}
+
+#[test]
+fn subr() {
+    let code = "\"RBUS\"4($$5011C.12J\n2*1R\n 7.@";
+
+    test_output(code, "10 7 ");
+}
+
+#[test]
+fn subr_relative() {
+    let code = "\"RBUS\"4($$O0{3011C.@\n             2*1R";
+
+    test_output(code, "6 ");
+}
+
+#[test]
+fn subr_missing_arguments() {
+    let code = "\"RBUS\"4($$5012C..@\n+1R";
+
+    test_output(code, "5 0 ");
+}
+
+#[test]
+fn subr_huge_count() {
+    let input = "2147483647\n";
+
+    test_output_with("\"RBUS\"4($$700&2j@.C", "7 ", |config| config.input(Cursor::new(input)));
+    test_output_with("\"RBUS\"4($$7&2j@.R", "7 ", |config| config.input(Cursor::new(input)));
+}
+
+#[test]
+fn subr_big_cell() {
+    let code = "\"RBUS\"4($$88*:*:*:*88*:**8*011C.@\n2*1R";
+
+    test_output_with(code, "18446744073709551616 ", |config| {
+        config.cell_size(CellSize::Unbounded)
+    });
+}
+
+#[test]
+fn sock() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut data = [0; 2];
+
+        stream.read_exact(&mut data).unwrap();
+        stream.write_all(&data).unwrap();
+    });
+
+    let code = "\"KCOS\"4($$221S:02p2&0\"1.0.0.721\"IC01202gW.03202gR.03g,13g,02gK@\nhi";
+    let input = format!("{}\n", port).into_bytes();
+
+    test_output_with(code, "2 2 hi", |config| {
+        config.input(Cursor::new(input)).network_view(NetworkView::Loopback)
+    });
+
+    server.join().unwrap();
+}
+
+#[test]
+fn sock_bind_in_use() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let code = "\"KCOS\"4($$221S2&0\"1.0.0.721\"I#vB0.@\n                              >1.@";
+    let input = format!("{}\n", port).into_bytes();
+
+    test_output_with(code, "1 ", |config| config.input(Cursor::new(input)));
+}
+
+#[test]
+fn sock_loopback() {
+    let code = "\"KCOS\"4($$221S2a0\"8.8.8.8\"I#vC0.@\n                            >1.@";
+
+    test_output_with(code, "1 ", |config| config.network_view(NetworkView::Loopback));
+}
+
+#[test]
+fn sock_deny() {
+    let code = "\"KCOS\"4($$221#vS0.@\n              >1.@";
+
+    test_output_with(code, "1 ", |config| config.network_view(NetworkView::Deny));
+}
+
+#[test]
+fn sock_datagram() {
+    let code = "\"KCOS\"4($$211#vS0.@\n              >1.@";
+
+    test_output(code, "1 ");
+}
+
+#[test]
+fn turt() {
+    let code = "\"TRUT\"4($$1PaF9a*RaFQ..A.IU....@";
+    let mut output = Vec::new();
+    let mut drawing = Vec::new();
+
+    {
+        let config = Config::new()
+            .input(io::empty())
+            .output(&mut output)
+            .drawing_output(&mut drawing);
+
+        Program::read(code).config(config).run();
+    }
+
+    let svg = "\
+        <svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -1 12 12\">\n\
+        <rect x=\"-1\" y=\"-1\" width=\"12\" height=\"12\" fill=\"#ffffff\"/>\n\
+        <line x1=\"0\" y1=\"0\" x2=\"10\" y2=\"0\" stroke=\"#000000\" stroke-linecap=\"round\"/>\n\
+        <line x1=\"10\" y1=\"0\" x2=\"10\" y2=\"10\" stroke=\"#000000\" stroke-linecap=\"round\"/>\n\
+        </svg>\n";
+
+    assert_eq!(&b"10 10 90 10 10 0 0 "[..], &*output);
+    assert_eq!(svg.as_bytes(), &*drawing);
+}
+
+#[test]
+fn three_dsp() {
+    let code = "\"PSPF\"4($$\"PSD3\"4($$1F2F3F4F5F6FCI.I.I.1F2F3F4F5F6FDI.2F3F6FLI.011F2F3FT1F1F1F01XI.I.I.4139a*FR1F0F0F41XI.I.I.@";
+
+    test_output(code, "-3 6 -3 32 7 4 3 2 0 1 0 ");
+}
+
+#[test]
+fn term() {
+    let code = "\"MRET\"4($$C34GL2US@";
+
+    test_output(code, "\x1b[2J\x1b[H\x1b[5;4H\x1b[K\x1b[2A\x1b[J");
+}
+
+#[test]
+fn ncrs() {
+    let code = "\"SRCN\"4($$1IB0\"ih\"S'aP12MR0I@";
+
+    test_output(code, "\x1b[?1049h\x1b[H\x07hia\x1b[3;2H\x1b[?1049l");
+}
+
+#[test]
+fn evar() {
+    let code = "\"RAVE\"4($$N.0\"A\"G,$0\"B\"G,,$0\"3=C\"PN.1V,,,,$0\"9=A\"P0\"A\"G,$@";
+
+    test_output_with(code, "2 1xy3 B=xy9", |config| config.env(vec![("A", "1"), ("B", "xy")]));
+}
+
+#[test]
+fn evar_sysinfo() {
+    let code = "\"EDOM\"4($$0yQ$$,,,@";
+
+    test_output_with(code, "b=A", |config| config.env(vec![("A", "b")]));
+}
+
+#[cfg(unix)]
+#[test]
+fn evar_non_unicode() {
+    use std::env;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    env::set_var("MYCON_TEST_EVAR", OsStr::from_bytes(b"\xff"));
+
+    let code = "\"RAVE\"4($$0\"RAVE_TSET_NOCYM\"G..@";
+
+    test_output(code, "65533 0 ");
+}
+
+#[test]
+fn frth() {
+    let code = "\"HTRF\"4($$123D.O.R2P.1L01-L...@";
+
+    test_output(code, "3 2 2 1 3 2 ");
+}
+
+#[test]
+fn frth_roll_below_bottom() {
+    let code = "\"HTRF\"4($$120fff**-#vL@\n                    >D.@";
+
+    test_output(code, "2 ");
+}
+
+#[test]
+fn jstr() {
+    let code = "\"RTSJ\"4($$\"cba\"10013P10013G,,,.@";
+
+    test_output(code, "abc0 ");
+}
+
+#[test]
+fn jstr_huge_count() {
+    let code = "\"RTSJ\"4($$71000&2j@.G";
+    let input = "9223372036854775807\n";
+
+    test_output_with(code, "7 ", |config| {
+        config.input(Cursor::new(input)).cell_size(CellSize::Bits64)
+    });
+}
+
+#[test]
+fn date() {
+    let code = "\"ETAD\"4($$&&&J.&&&W.&&&Y.&&&&A...&&&&&&D.&C...&&T...@";
+    let input = "2000\n1\n1\n2000\n1\n1\n2000\n3\n1\n1999\n12\n31\n1\n2000\n3\n1\n2000\n1\n1\n2451545\n2000\n60\n";
+
+    test_output_with(code, "2451545 5 60 1 1 2000 60 1 1 2000 1 3 2000 ", |config| {
+        config.input(Cursor::new(input))
+    });
+}
+
+#[test]
+fn time() {
+    let code = "\"EMIT\"4($$GY.O.D.@";
+    let date = || {
+        let now = Utc::now();
+
+        format!("{} {} {} ", now.year(), now.month(), now.day())
+    };
+    let mut output = Vec::new();
+
+    let before = date();
+
+    {
+        let config = Config::new().input(io::empty()).output(&mut output);
+
+        Program::read(code).config(config).run();
+    }
+
+    let after = date();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output == before || output == after, "{}", output);
+}
@@ -15,20 +15,73 @@
 // You should have received a copy of the GNU General Public License
 // along with mycon.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::env;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
 
-use mycon::{Config, Program};
+use mycon::{Befunge, Config, Dimension, Program};
 
+#[allow(dead_code)]
 pub fn test_output(code: &str, output: &str) {
+    test_output_with(code, output, |config| config);
+}
+
+#[allow(dead_code)]
+pub fn test_output_with<F>(code: &str, output: &str, f: F)
+where
+    F: FnOnce(Config) -> Config,
+{
+    run_test(code, Befunge, output, f);
+}
+
+#[allow(dead_code)]
+pub fn test_output_in<D: Dimension>(code: &str, dimension: D, output: &str) {
+    run_test(code, dimension, output, |config| config);
+}
+
+fn run_test<D, F>(code: &str, dimension: D, output: &str, f: F)
+where
+    D: Dimension,
+    F: FnOnce(Config) -> Config,
+{
     let mut empty = io::empty();
     let mut buffer = Vec::new();
 
     {
-        let config = Config::new().input(&mut empty).output(&mut buffer);
-        let mut prog = Program::read(code).config(config);
+        let config = f(Config::new().input(&mut empty).output(&mut buffer));
+        let mut prog = Program::read_in(code, dimension).config(config);
 
         prog.run();
     }
 
     assert_eq!(output.as_bytes(), &*buffer);
 }
+
+/// A temporary directory unique to a single test, removed when dropped.
+#[allow(dead_code)]
+pub struct TempDir(PathBuf);
+
+#[allow(dead_code)]
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let dir = format!("mycon_test_{}_{}", name, process::id());
+        let path = env::temp_dir().join(dir);
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir(&path).unwrap();
+
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}